@@ -0,0 +1,22 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use tttai::{best_move, Board, MiniMaxNode};
+
+fn search(c: &mut Criterion) {
+    let positions = [
+        ("empty", Board::new()),
+        ("corner opening", Board::from_moves(&[0]).unwrap()),
+        ("midgame", Board::from_moves(&[4, 0, 8, 2]).unwrap()),
+    ];
+    for (name, board) in &positions {
+        c.bench_function(&format!("best_move {}", name), |b| {
+            b.iter(|| best_move(std::hint::black_box(board)))
+        });
+    }
+    // The openings above come out of the book, so also time a search from scratch.
+    c.bench_function("search empty", |b| {
+        b.iter(|| MiniMaxNode::new(std::hint::black_box(&Board::new())).calculate())
+    });
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
@@ -0,0 +1,10 @@
+fn main() {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    println!("Listening on {}", addr);
+    if let Err(e) = tttai::server::serve(&addr) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,1386 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Index;
+use core::str::FromStr;
+use core::{iter, slice};
+
+/// The most cells a board can have, so that every cell index fits in the `u8`
+/// moves the search returns.
+pub const MAX_CELLS: usize = 256;
+
+/// Marks order like their `to_value`: `O < X`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mark {
+    O,
+    X,
+}
+
+impl Mark {
+    pub fn other(&self) -> Mark {
+        match self {
+            Mark::O => Mark::X,
+            Mark::X => Mark::O,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::O => 'O',
+            Self::X => 'X',
+        }
+    }
+
+    /// The mark written as `c`, in either case.
+    pub fn from_char(c: char) -> Option<Mark> {
+        match c {
+            'X' | 'x' => Some(Mark::X),
+            'O' | 'o' => Some(Mark::O),
+            _ => None,
+        }
+    }
+
+    // ANSI escape that starts this mark's color: red for X, blue for O.
+    fn color(self) -> &'static str {
+        match self {
+            Self::O => "\x1B[34m",
+            Self::X => "\x1B[31m",
+        }
+    }
+
+    pub fn to_value(self) -> i8 {
+        match self {
+            Self::O => -1,
+            Self::X => 1,
+        }
+    }
+}
+
+impl fmt::Display for Mark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "mark"))]
+pub enum State {
+    Turn(Mark),
+    Won(Mark),
+    Tie,
+}
+
+/// The rules for what completing a line means. Lines are found the same way
+/// either way; only who it counts for changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variant {
+    /// Completing a line wins.
+    #[default]
+    Standard,
+    /// Completing a line loses, so `State::Won` names the other side.
+    Misere,
+}
+
+/// How many ways a game can end from some position, counting every sequence of
+/// legal moves separately.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Outcomes {
+    pub x_wins: u64,
+    pub o_wins: u64,
+    pub draws: u64,
+}
+
+impl Outcomes {
+    /// The number of distinct games.
+    pub fn total(&self) -> u64 {
+        self.x_wins + self.o_wins + self.draws
+    }
+}
+
+/// How `Board::render` draws the grid. The default matches `Display`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Color X red and O blue with ANSI escapes.
+    pub color: bool,
+    /// Show each empty cell's index instead of a blank.
+    pub guides: bool,
+    pub style: BoardStyle,
+    pub theme: Theme,
+}
+
+/// What `Board::evaluate_with` rewards, per line or per mark, for the side that
+/// has it. The default is what `Board::evaluate` and `TwoInARowEvaluator` use.
+/// Only depth-limited searches ever score a position this way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeuristicWeights {
+    /// A mark on a center cell, or one of the middle four on an even board.
+    pub center: i32,
+    /// A mark in a corner.
+    pub corner: i32,
+    /// A line only one side has marks in, but more than one short of complete.
+    pub open_line: i32,
+    /// A line only one side has marks in and one mark short of complete: on the
+    /// 3x3 board, an open two.
+    pub one_short: i32,
+}
+
+impl Default for HeuristicWeights {
+    fn default() -> Self {
+        HeuristicWeights {
+            center: 0,
+            corner: 0,
+            open_line: 1,
+            one_short: 2,
+        }
+    }
+}
+
+/// The characters the marks are drawn as, say a pair of emoji. Only rendering and
+/// parsing use it; the marks themselves are still `Mark::X` and `Mark::O`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Drawn for `Mark::X`.
+    pub first: char,
+    /// Drawn for `Mark::O`.
+    pub second: char,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            first: 'X',
+            second: 'O',
+        }
+    }
+}
+
+impl Theme {
+    pub fn glyph(&self, mark: Mark) -> char {
+        match mark {
+            Mark::X => self.first,
+            Mark::O => self.second,
+        }
+    }
+
+    /// The mark drawn as `c`. The plain letters are still understood unless one
+    /// of the glyphs is already using them.
+    pub fn mark(&self, c: char) -> Option<Mark> {
+        if c == self.first {
+            Some(Mark::X)
+        } else if c == self.second {
+            Some(Mark::O)
+        } else {
+            Mark::from_char(c)
+        }
+    }
+}
+
+/// How the cells of a grid are separated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoardStyle {
+    /// `X| |O`, one row per line. This is what `Display` uses.
+    #[default]
+    Plain,
+    /// Padded cells between Unicode box-drawing lines.
+    Boxed,
+    /// `X.O`, with no separators and `.` for empty cells, so it parses back.
+    Compact,
+    /// `X.O|..X|O.X`, the compact rows on a single line, which also parses back.
+    OneLine,
+}
+
+// The move history is bookkeeping for `undo` and is left out of equality and
+// hashing, so positions reached through different move orders still match.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "BoardData"))]
+pub struct Board {
+    pub(crate) marks: Vec<Option<Mark>>,
+    // The number of columns; `height` is the number of rows.
+    size: usize,
+    height: usize,
+    win_len: usize,
+    variant: Variant,
+    gravity: bool,
+    state: State,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Vec<usize>,
+}
+
+// What a serialized board holds; it only becomes a `Board` once it validates.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct BoardData {
+    marks: Vec<Option<Mark>>,
+    size: usize,
+    // Square if missing, as every board was before these were added.
+    height: Option<usize>,
+    win_len: usize,
+    #[serde(default)]
+    variant: Variant,
+    #[serde(default)]
+    gravity: bool,
+    state: State,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BoardData> for Board {
+    type Error = BoardError;
+
+    fn try_from(data: BoardData) -> Result<Self, Self::Error> {
+        let board = Board {
+            marks: data.marks,
+            size: data.size,
+            height: data.height.unwrap_or(data.size),
+            win_len: data.win_len,
+            variant: data.variant,
+            gravity: data.gravity,
+            state: data.state,
+            history: Vec::new(),
+        };
+        board.validate()?;
+        Ok(board)
+    }
+}
+
+// Boards are the same position if they have the same rules, marks and side to
+// move. The cached `state` is otherwise derived from the marks, so it is left out.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.height == other.height
+            && self.win_len == other.win_len
+            && self.variant == other.variant
+            && self.gravity == other.gravity
+            && self.marks == other.marks
+            && self.side_to_move() == other.side_to_move()
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.height.hash(state);
+        self.win_len.hash(state);
+        self.variant.hash(state);
+        self.gravity.hash(state);
+        self.marks.hash(state);
+        self.side_to_move().hash(state);
+    }
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Self::with_first_player(Mark::X)
+    }
+
+    pub fn with_first_player(mark: Mark) -> Self {
+        let mut board = Self::with_size(3, 3);
+        board.state = State::Turn(mark);
+        board
+    }
+
+    /// A standard board after playing `moves` in order, X first.
+    pub fn from_moves(moves: &[usize]) -> Result<Board, PlaceError> {
+        let mut board = Board::new();
+        for &index in moves {
+            board.place(index)?;
+        }
+        Ok(board)
+    }
+
+    /// An empty `size` by `size` board where `win_len` marks in a row win.
+    /// Panics unless `1 <= win_len <= size` and the board has at most `MAX_CELLS`
+    /// cells.
+    pub fn with_size(size: usize, win_len: usize) -> Self {
+        Self::with_dimensions(size, size, win_len)
+    }
+
+    /// An empty board `width` columns wide and `height` rows tall where `win_len`
+    /// marks in a row win. Panics unless the win length fits both ways and the
+    /// board has at most `MAX_CELLS` cells.
+    pub fn with_dimensions(width: usize, height: usize, win_len: usize) -> Self {
+        assert!(
+            (1..=width.min(height)).contains(&win_len),
+            "win length {} does not fit on a {}x{} board",
+            win_len,
+            width,
+            height
+        );
+        assert!(
+            width * height <= MAX_CELLS,
+            "a {}x{} board has more than {} cells",
+            width,
+            height,
+            MAX_CELLS
+        );
+        Board {
+            marks: vec![None; width * height],
+            size: width,
+            height,
+            win_len,
+            variant: Variant::Standard,
+            gravity: false,
+            state: State::Turn(Mark::X),
+            history: Vec::new(),
+        }
+    }
+
+    /// Like `with_dimensions`, but marks fall to the lowest empty cell of their
+    /// column, as in Connect Four. Only those cells are legal moves, so a search
+    /// has one move per column that isn't full; `place_column` plays by column.
+    pub fn with_gravity(width: usize, height: usize, win_len: usize) -> Self {
+        let mut board = Self::with_dimensions(width, height, win_len);
+        board.gravity = true;
+        board
+    }
+
+    /// The number of columns, which is also the number of rows unless the board
+    /// came from `with_dimensions` or `with_gravity`.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn has_gravity(&self) -> bool {
+        self.gravity
+    }
+
+    pub fn win_len(&self) -> usize {
+        self.win_len
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Switches the rules, working out again who has won if a line is complete.
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = variant;
+        self.state = self.get_new_state();
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn winner(&self) -> Option<Mark> {
+        match self.state {
+            State::Won(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Who moves next, or `None` once the game is over.
+    pub fn current_player(&self) -> Option<Mark> {
+        match self.state {
+            State::Turn(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        !matches!(self.state, State::Turn(_))
+    }
+
+    /// Indices of the empty cells, or nothing once the game is over. With gravity
+    /// only the lowest empty cell of each column counts.
+    pub fn legal_moves(&self) -> impl Iterator<Item = usize> + '_ {
+        let playing = !self.is_over();
+        self.marks
+            .iter()
+            .enumerate()
+            .filter(move |&(i, m)| playing && m.is_none() && self.is_supported(i))
+            .map(|(i, _)| i)
+    }
+
+    // Whether a mark can rest at `index`: always, unless gravity would pull it into
+    // an empty cell below.
+    fn is_supported(&self, index: usize) -> bool {
+        let below = index + self.size;
+        !self.gravity || below >= self.marks.len() || self.marks[below].is_some()
+    }
+
+    /// The cells played so far, in order. Boards built by parsing start with none.
+    pub fn history(&self) -> &[usize] {
+        &self.history
+    }
+
+    pub fn marks(&self) -> &[Option<Mark>] {
+        &self.marks
+    }
+
+    /// Every cell's index and mark, in index order. Iterating `&Board` does the same.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, Option<Mark>)> + '_ {
+        self.into_iter()
+    }
+
+    /// Whether both boards have the same shape and the same marks in the same
+    /// cells. Unlike `==` this ignores whose turn it is and the rules.
+    pub fn same_marks(&self, other: &Board) -> bool {
+        self.size == other.size && self.height == other.height && self.marks == other.marks
+    }
+
+    #[cfg(feature = "std")]
+    pub fn print(&self) {
+        print!("{}", self);
+    }
+
+    /// The same grid as `Display`, drawn according to `options`.
+    pub fn render(&self, options: RenderOptions) -> String {
+        let mut out = String::new();
+        self.write_grid(&mut out, options).unwrap();
+        out
+    }
+
+    fn write_grid(&self, f: &mut impl fmt::Write, options: RenderOptions) -> fmt::Result {
+        let (n, rows) = (self.size, self.height);
+        let boxed = options.style == BoardStyle::Boxed;
+        // Boxed cells are padded to the widest guide so the separators line up.
+        let width = match options.guides {
+            true => (self.marks.len() - 1).to_string().len(),
+            false => 1,
+        };
+        for row in 0..rows {
+            if boxed && row > 0 {
+                for col in 0..n {
+                    if col > 0 {
+                        write!(f, "┼")?;
+                    }
+                    write!(f, "{}", "─".repeat(width + 2))?;
+                }
+                writeln!(f)?;
+            }
+            for col in 0..n {
+                let i = row * n + col;
+                match (col, options.style) {
+                    (0, _) | (_, BoardStyle::Compact | BoardStyle::OneLine) => {}
+                    (_, BoardStyle::Plain) => write!(f, "|")?,
+                    (_, BoardStyle::Boxed) => write!(f, "│")?,
+                }
+                if boxed {
+                    write!(f, " ")?;
+                }
+                let shown = match self.marks[i] {
+                    None if options.guides => {
+                        write!(f, "{}", i)?;
+                        i.to_string().len()
+                    }
+                    None if matches!(options.style, BoardStyle::Compact | BoardStyle::OneLine) => {
+                        write!(f, ".")?;
+                        1
+                    }
+                    None => {
+                        write!(f, " ")?;
+                        1
+                    }
+                    Some(m) if options.color => {
+                        write!(f, "{}{}\x1B[0m", m.color(), options.theme.glyph(m))?;
+                        1
+                    }
+                    Some(m) => {
+                        write!(f, "{}", options.theme.glyph(m))?;
+                        1
+                    }
+                };
+                if boxed {
+                    write!(f, "{} ", " ".repeat(width - shown))?;
+                }
+            }
+            match options.style {
+                BoardStyle::OneLine if row + 1 < rows => write!(f, "|")?,
+                _ => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// A self-contained SVG picture of the board: the grid, a glyph in every marked
+    /// cell and, once the game is won, a stroke through the winning line.
+    pub fn to_svg(&self) -> String {
+        let mut out = String::new();
+        self.write_svg(&mut out).unwrap();
+        out
+    }
+
+    fn write_svg(&self, svg: &mut impl fmt::Write) -> fmt::Result {
+        const CELL: usize = 100;
+        let n = self.size;
+        let (width, height) = (n * CELL, self.height * CELL);
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        )?;
+        writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="white"/>"#,
+            width, height
+        )?;
+        for at in (1..n).map(|k| k * CELL) {
+            writeln!(
+                svg,
+                r#"<line x1="{0}" y1="0" x2="{0}" y2="{1}" stroke="black" stroke-width="4"/>"#,
+                at, height
+            )?;
+        }
+        for at in (1..self.height).map(|k| k * CELL) {
+            writeln!(
+                svg,
+                r#"<line x1="0" y1="{0}" x2="{1}" y2="{0}" stroke="black" stroke-width="4"/>"#,
+                at, width
+            )?;
+        }
+        let center = |i: usize| ((i % n) * CELL + CELL / 2, (i / n) * CELL + CELL / 2);
+        for (i, mark) in self.cells() {
+            let Some(mark) = mark else { continue };
+            let (x, y) = center(i);
+            let color = match mark {
+                Mark::X => "#c00",
+                Mark::O => "#00c",
+            };
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" font-size="80" font-family="sans-serif" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+                x,
+                y,
+                color,
+                mark.to_char()
+            )?;
+        }
+        if let Some(((start, step), _)) = self.completed_line() {
+            let (x1, y1) = center(start);
+            let (x2, y2) = center(start + (self.win_len - 1) * step);
+            writeln!(
+                svg,
+                r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#2a2" stroke-width="10" stroke-linecap="round"/>"##,
+                x1, y1, x2, y2
+            )?;
+        }
+        writeln!(svg, "</svg>")
+    }
+
+    // Every run of `win_len` cells in a straight line as (first cell, step between
+    // cells): rows, then columns, then both diagonal directions.
+    pub(crate) fn lines(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (n, h, k) = (self.size, self.height, self.win_len);
+        // How many starting columns and rows leave room for a whole line.
+        let (across, down) = (n + 1 - k, h + 1 - k);
+        let rows = (0..h).flat_map(move |r| (0..across).map(move |c| (r * n + c, 1)));
+        let cols = (0..down).flat_map(move |r| (0..n).map(move |c| (r * n + c, n)));
+        let diag = (0..down).flat_map(move |r| (0..across).map(move |c| (r * n + c, n + 1)));
+        let anti = (0..down).flat_map(move |r| (k - 1..n).map(move |c| (r * n + c, n - 1)));
+        rows.chain(cols).chain(diag).chain(anti)
+    }
+
+    pub(crate) fn line_cells(&self, (start, step): (usize, usize)) -> impl Iterator<Item = usize> {
+        (0..self.win_len).map(move |i| start + i * step)
+    }
+
+    // Whose turn it is going by the marks, so `None` on a won or full board whatever
+    // the cached state says.
+    fn side_to_move(&self) -> Option<Mark> {
+        if self.completed_line().is_some() || self.marks.iter().all(Option::is_some) {
+            return None;
+        }
+        self.current_player()
+    }
+
+    // The first line that one mark fills completely.
+    fn completed_line(&self) -> Option<((usize, usize), Mark)> {
+        self.lines().find_map(|line| {
+            let mark = self.marks[line.0]?;
+            self.line_cells(line)
+                .all(|i| self.marks[i] == Some(mark))
+                .then_some((line, mark))
+        })
+    }
+
+    /// Checks that the board could come up in a real game: the cells fill the grid,
+    /// the mark counts differ by at most one and agree with whose turn it is, no
+    /// more than one side has completed a line and, with gravity, no mark floats
+    /// above an empty cell.
+    pub fn validate(&self) -> Result<(), BoardError> {
+        let fits = (1..=self.size.min(self.height)).contains(&self.win_len);
+        let cells = self.size * self.height;
+        if self.marks.len() != cells || cells > MAX_CELLS || !fits {
+            return Err(BoardError::Shape);
+        }
+        if self
+            .cells()
+            .any(|(i, m)| m.is_some() && !self.is_supported(i))
+        {
+            return Err(BoardError::Floating);
+        }
+        let count = |mark| self.marks.iter().filter(|&&m| m == Some(mark)).count();
+        let (x, o) = (count(Mark::X), count(Mark::O));
+        let parity_ok = match self.state {
+            // Equal counts mean the side on turn also went first, which either may have.
+            State::Turn(_) if x == o => true,
+            State::Turn(Mark::O) => x == o + 1,
+            State::Turn(Mark::X) => o == x + 1,
+            State::Won(_) | State::Tie => x.abs_diff(o) <= 1,
+        };
+        if !parity_ok {
+            return Err(BoardError::MarkCount);
+        }
+        let has_line = |mark| {
+            self.lines()
+                .any(|line| self.line_cells(line).all(|i| self.marks[i] == Some(mark)))
+        };
+        if has_line(Mark::X) && has_line(Mark::O) {
+            return Err(BoardError::BothWon);
+        }
+        let ended = match self.state {
+            State::Turn(_) => None,
+            ref state => Some(state.clone()),
+        };
+        if self.outcome() != ended {
+            return Err(BoardError::StaleState);
+        }
+        Ok(())
+    }
+
+    /// The position packed into 19 bits: two per cell in index order (0 empty, 1 X,
+    /// 2 O), then one set when O is to move. The history is dropped, and only the
+    /// standard 3x3 board fits, so any other shape or rules panic.
+    pub fn encode(&self) -> u32 {
+        assert!(
+            self.size == 3 && self.height == 3 && self.win_len == 3,
+            "only the 3x3 board has a u32 encoding"
+        );
+        assert!(
+            self.variant == Variant::Standard && !self.gravity,
+            "only the standard rules have a u32 encoding"
+        );
+        let cells = self.cells().fold(0, |code, (i, m)| {
+            let bits = match m {
+                None => 0,
+                Some(Mark::X) => 1,
+                Some(Mark::O) => 2,
+            };
+            code | bits << (2 * i)
+        });
+        let o_to_move = self.side_to_move() == Some(Mark::O);
+        cells | (o_to_move as u32) << 18
+    }
+
+    /// The board `encode` turns into `code`, or `None` if `code` isn't one it
+    /// produces for a legal position.
+    pub fn decode(code: u32) -> Option<Board> {
+        if code >> 19 != 0 {
+            return None;
+        }
+        let marks = (0..9)
+            .map(|i| match code >> (2 * i) & 3 {
+                0 => Some(None),
+                1 => Some(Some(Mark::X)),
+                2 => Some(Some(Mark::O)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let o_to_move = code >> 18 == 1;
+        let mut board = Board {
+            marks,
+            size: 3,
+            height: 3,
+            win_len: 3,
+            variant: Variant::Standard,
+            gravity: false,
+            state: State::Turn(if o_to_move { Mark::O } else { Mark::X }),
+            history: Vec::new(),
+        };
+        board.state = board.get_new_state();
+        // A finished game has nobody to move, so `encode` never sets the bit for one.
+        if o_to_move && board.is_over() {
+            return None;
+        }
+        board.validate().ok()?;
+        Some(board)
+    }
+
+    /// A copy of the cells in index order. Panics unless the board has nine cells.
+    pub fn marks_array(&self) -> [Option<Mark>; 9] {
+        self.marks
+            .as_slice()
+            .try_into()
+            .expect("only a board of nine cells fits in the array")
+    }
+
+    /// The standard 3x3 board with these cells, in index order. As when parsing,
+    /// the side to move follows from the mark counts, with X to move when they're
+    /// equal, and the position has to pass `validate`.
+    pub fn from_marks(marks: [Option<Mark>; 9]) -> Result<Board, BoardError> {
+        let count = |mark| marks.iter().filter(|&&m| m == Some(mark)).count();
+        let (x, o) = (count(Mark::X), count(Mark::O));
+        let turn = match x.cmp(&o) {
+            Ordering::Equal => Mark::X,
+            Ordering::Greater if x == o + 1 => Mark::O,
+            Ordering::Less if o == x + 1 => Mark::X,
+            _ => return Err(BoardError::MarkCount),
+        };
+        let mut board = Board {
+            marks: marks.to_vec(),
+            size: 3,
+            height: 3,
+            win_len: 3,
+            variant: Variant::Standard,
+            gravity: false,
+            state: State::Turn(turn),
+            history: Vec::new(),
+        };
+        board.state = board.get_new_state();
+        board.validate()?;
+        Ok(board)
+    }
+
+    /// Every line that doesn't hold both marks, as its cells together with the mark
+    /// that can still complete it, or `None` if the line is empty.
+    pub fn open_lines(&self) -> Vec<(Vec<usize>, Option<Mark>)> {
+        self.lines()
+            .filter_map(|line| {
+                let cells: Vec<usize> = self.line_cells(line).collect();
+                let mut owner = None;
+                for &i in &cells {
+                    match (owner, self.marks[i]) {
+                        (Some(a), Some(b)) if a != b => return None,
+                        (_, Some(b)) => owner = Some(b),
+                        (_, None) => {}
+                    }
+                }
+                Some((cells, owner))
+            })
+            .collect()
+    }
+
+    /// The lines `mark` could complete with one more move: all its own marks but a
+    /// single empty cell.
+    pub fn threats(&self, mark: Mark) -> Vec<Vec<usize>> {
+        self.open_lines()
+            .into_iter()
+            .filter(|(cells, owner)| {
+                *owner == Some(mark)
+                    && cells.iter().filter(|&&i| self.marks[i].is_none()).count() == 1
+            })
+            .map(|(cells, _)| cells)
+            .collect()
+    }
+
+    /// True while the game is still going but neither side can complete a line any
+    /// more, even using every move they have left, so it can only end in a tie.
+    pub fn is_forced_draw(&self) -> bool {
+        !self.is_over() && !self.can_complete_line(Mark::X) && !self.can_complete_line(Mark::O)
+    }
+
+    /// Whether `mark` could still win: some line can still be completed by whoever
+    /// completing it would make `mark` the winner, in the moves they have left.
+    /// Only marks and moves are counted, not blocking, so this doesn't mean the win
+    /// can be forced. A finished game is only still a win for its winner.
+    pub fn can_win(&self, mark: Mark) -> bool {
+        match self.state {
+            State::Won(m) => m == mark,
+            State::Tie => false,
+            State::Turn(_) => match self.variant {
+                Variant::Standard => self.can_complete_line(mark),
+                Variant::Misere => self.can_complete_line(mark.other()),
+            },
+        }
+    }
+
+    // Whether some line holds none of the opponent's marks and has no more empty
+    // cells than `mark` has moves left to fill them.
+    fn can_complete_line(&self, mark: Mark) -> bool {
+        let empty = self.empty_count();
+        let moves_left = match self.state {
+            State::Turn(m) if m == mark => empty.div_ceil(2),
+            _ => empty / 2,
+        };
+        self.lines().any(|line| {
+            let mut blanks = 0;
+            for i in self.line_cells(line) {
+                match self.marks[i] {
+                    Some(m) if m != mark => return false,
+                    Some(_) => {}
+                    None => blanks += 1,
+                }
+            }
+            blanks <= moves_left
+        })
+    }
+
+    pub fn winning_line(&self) -> Option<Vec<usize>> {
+        match self.state {
+            State::Won(_) => self
+                .completed_line()
+                .map(|(line, _)| self.line_cells(line).collect()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_new_state(&self) -> State {
+        self.outcome().unwrap_or_else(|| self.state.clone())
+    }
+
+    // How the marks have ended the game, if they have.
+    fn outcome(&self) -> Option<State> {
+        if let Some((_, mark)) = self.completed_line() {
+            return Some(match self.variant {
+                Variant::Standard => State::Won(mark),
+                Variant::Misere => State::Won(mark.other()),
+            });
+        }
+        self.marks.iter().all(Option::is_some).then_some(State::Tie)
+    }
+
+    /// Whether `place(index)` would succeed, and the error it would return if not,
+    /// without changing the board.
+    pub fn can_place(&self, index: usize) -> Result<(), PlaceError> {
+        if index >= self.marks.len() {
+            return Err(PlaceError::OutOfRange);
+        }
+        if self.marks[index].is_some() {
+            return Err(PlaceError::Occupied);
+        }
+        if !self.is_supported(index) {
+            return Err(PlaceError::Unsupported);
+        }
+        match self.state {
+            State::Turn(_) => Ok(()),
+            _ => Err(PlaceError::GameOver),
+        }
+    }
+
+    pub fn place(&mut self, index: usize) -> Result<(), PlaceError> {
+        self.can_place(index)?;
+        let State::Turn(mark) = self.state else {
+            unreachable!("can_place only allows moves in a game in progress");
+        };
+        self.marks[index] = Some(mark);
+        self.history.push(index);
+        self.state = State::Turn(mark.other());
+        self.state = self.get_new_state();
+        Ok(())
+    }
+
+    /// Places a mark by row and column, in `0..height` and `0..size`.
+    pub fn place_rc(&mut self, row: usize, col: usize) -> Result<(), PlaceError> {
+        if row >= self.height || col >= self.size {
+            return Err(PlaceError::OutOfRange);
+        }
+        self.place(row * self.size + col)
+    }
+
+    /// Drops a mark into the lowest empty cell of column `col` and returns the
+    /// index it landed on. A full column is `PlaceError::Occupied`.
+    pub fn place_column(&mut self, col: usize) -> Result<usize, PlaceError> {
+        if col >= self.size {
+            return Err(PlaceError::OutOfRange);
+        }
+        let index = (0..self.height)
+            .rev()
+            .map(|row| row * self.size + col)
+            .find(|&i| self.marks[i].is_none())
+            .ok_or(PlaceError::Occupied)?;
+        self.place(index)?;
+        Ok(index)
+    }
+
+    /// The mark at `(row, col)`, or `None` for an empty or off-board cell.
+    pub fn mark_at(&self, row: usize, col: usize) -> Option<Mark> {
+        if row >= self.height || col >= self.size {
+            return None;
+        }
+        self.marks[row * self.size + col]
+    }
+
+    /// Counts the result of every possible continuation from here, not just the
+    /// ones perfect play would choose. A finished board counts as its own result.
+    #[cfg(feature = "std")]
+    pub fn enumerate_outcomes(&self) -> Outcomes {
+        self.clone()
+            .count_outcomes(&mut std::collections::HashMap::new())
+    }
+
+    // Symmetric positions have the same counts, so they share a memo entry.
+    #[cfg(feature = "std")]
+    fn count_outcomes(
+        &mut self,
+        seen: &mut std::collections::HashMap<Board, Outcomes>,
+    ) -> Outcomes {
+        match self.state {
+            State::Won(Mark::X) => {
+                return Outcomes {
+                    x_wins: 1,
+                    ..Outcomes::default()
+                }
+            }
+            State::Won(Mark::O) => {
+                return Outcomes {
+                    o_wins: 1,
+                    ..Outcomes::default()
+                }
+            }
+            State::Tie => {
+                return Outcomes {
+                    draws: 1,
+                    ..Outcomes::default()
+                }
+            }
+            State::Turn(_) => {}
+        }
+        let key = self.canonical();
+        if let Some(&outcomes) = seen.get(&key) {
+            return outcomes;
+        }
+        let mut total = Outcomes::default();
+        let moves: Vec<usize> = self.legal_moves().collect();
+        for index in moves {
+            self.place(index).unwrap();
+            let sub = self.count_outcomes(seen);
+            self.undo();
+            total.x_wins += sub.x_wins;
+            total.o_wins += sub.o_wins;
+            total.draws += sub.draws;
+        }
+        seen.insert(key, total);
+        total
+    }
+
+    /// Takes back the last move made with `place` and returns its index.
+    /// Boards built by parsing have no history to undo.
+    pub fn undo(&mut self) -> Option<usize> {
+        let index = self.history.pop()?;
+        let mark = self.marks[index].take()?;
+        self.state = State::Turn(mark);
+        Some(index)
+    }
+
+    /// A score for the position: positive favors X, negative favors O.
+    ///
+    /// A won game scores more than any unfinished position can. Otherwise every line
+    /// that only X has marks in counts 1, or 2 if X needs just one more mark to
+    /// complete it, and O's lines count the same against. Under `Variant::Misere`
+    /// an open line is a liability instead, so those counts change sign. This is
+    /// `evaluate_with` and the default `HeuristicWeights`.
+    pub fn evaluate(&self) -> i32 {
+        self.evaluate_with(&HeuristicWeights::default())
+    }
+
+    /// Like `evaluate`, but with the open lines counted and the center and corner
+    /// marks rewarded by `weights`. Under `Variant::Misere` only the line counts
+    /// change sign. However large the weights, an unfinished position is held to
+    /// less than a won game.
+    pub fn evaluate_with(&self, weights: &HeuristicWeights) -> i32 {
+        match self.state {
+            State::Won(m) => return m.to_value() as i32 * self.win_value(),
+            State::Tie => return 0,
+            State::Turn(_) => {}
+        }
+        let sign = match self.variant {
+            Variant::Standard => 1,
+            Variant::Misere => -1,
+        };
+        let (n, rows) = (self.size, self.height);
+        let middle = |i: usize, len: usize| (len - 1) / 2 <= i && i <= len / 2;
+        let cells: i32 = self
+            .cells()
+            .filter_map(|(i, m)| {
+                let (row, col) = (i / n, i % n);
+                let weight = if middle(row, rows) && middle(col, n) {
+                    weights.center
+                } else if (row == 0 || row == rows - 1) && (col == 0 || col == n - 1) {
+                    weights.corner
+                } else {
+                    0
+                };
+                Some(m?.to_value() as i32 * weight)
+            })
+            .sum();
+        let lines: i32 = self
+            .lines()
+            .map(|line| {
+                let (mut x, mut o) = (0, 0);
+                for i in self.line_cells(line) {
+                    match self.marks[i] {
+                        Some(Mark::X) => x += 1,
+                        Some(Mark::O) => o += 1,
+                        None => {}
+                    }
+                }
+                let weight = |n: usize| match n + 1 == self.win_len {
+                    true => weights.one_short,
+                    false => weights.open_line,
+                };
+                match (x, o) {
+                    (0, 0) => 0,
+                    (x, 0) => weight(x),
+                    (0, o) => -weight(o),
+                    _ => 0,
+                }
+            })
+            .sum();
+        let cap = self.win_value() - 1;
+        (sign * lines + cells).clamp(-cap, cap)
+    }
+
+    // Above the largest score an unfinished position can get from `evaluate`.
+    pub(crate) fn win_value(&self) -> i32 {
+        2 * self.lines().count() as i32 + 1
+    }
+
+    pub fn empty_count(&self) -> usize {
+        self.marks.iter().filter(|m| m.is_none()).count()
+    }
+
+    /// How many marks are on the board.
+    pub fn move_count(&self) -> usize {
+        self.marks.len() - self.empty_count()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.marks.iter().all(|m| m.is_some())
+    }
+
+    // Where each cell ends up under one of the eight symmetries of the square:
+    // `k % 4` clockwise quarter turns, after a left-right mirror when `k >= 4`.
+    // Only the mirror works on a board that isn't square.
+    pub(crate) fn symmetry(&self, k: usize) -> Vec<usize> {
+        let n = self.size;
+        assert!(
+            k.is_multiple_of(4) || n == self.height,
+            "only a square board can be turned"
+        );
+        (0..self.marks.len())
+            .map(|i| {
+                let (mut r, mut c) = (i / n, i % n);
+                if k >= 4 {
+                    c = n - 1 - c;
+                }
+                for _ in 0..k % 4 {
+                    (r, c) = (c, n - 1 - r);
+                }
+                r * n + c
+            })
+            .collect()
+    }
+
+    pub(crate) fn transformed(&self, perm: &[usize]) -> Board {
+        let mut marks = vec![None; self.marks.len()];
+        for (i, m) in self.cells() {
+            marks[perm[i]] = m;
+        }
+        Board {
+            marks,
+            size: self.size,
+            height: self.height,
+            win_len: self.win_len,
+            variant: self.variant,
+            gravity: self.gravity,
+            state: self.state.clone(),
+            history: self.history.iter().map(|&i| perm[i]).collect(),
+        }
+    }
+
+    // The symmetries that keep a position the same game. Turning a rectangle
+    // changes its shape, and anything but the mirror turns gravity sideways.
+    fn equivalent_symmetries(&self) -> &'static [usize] {
+        match self.gravity || self.size != self.height {
+            true => &[0, 4],
+            false => &[0, 1, 2, 3, 4, 5, 6, 7],
+        }
+    }
+
+    /// The canonical board together with the cell permutation that produces it.
+    pub(crate) fn canonical_with_symmetry(&self) -> (Board, Vec<usize>) {
+        self.equivalent_symmetries()
+            .iter()
+            .map(|&k| {
+                let perm = self.symmetry(k);
+                (self.transformed(&perm), perm)
+            })
+            .min_by(|(a, _), (b, _)| a.marks.cmp(&b.marks))
+            .unwrap()
+    }
+
+    /// Of the eight rotations and reflections of this board, the one whose cells
+    /// compare smallest (empty < O < X, in index order). Symmetric positions share it.
+    /// Rectangular and gravity boards only consider the left-right mirror.
+    pub fn canonical(&self) -> Board {
+        self.canonical_with_symmetry().0
+    }
+
+    // The board under `symmetry(k)`, with its state worked out again from the marks.
+    fn with_symmetry(&self, k: usize) -> Board {
+        let mut board = self.transformed(&self.symmetry(k));
+        board.state = board.get_new_state();
+        board
+    }
+
+    /// Turned a quarter clockwise, so the top row becomes the right column.
+    pub fn rotated_90(&self) -> Board {
+        self.with_symmetry(1)
+    }
+
+    pub fn rotated_180(&self) -> Board {
+        self.with_symmetry(2)
+    }
+
+    /// Turned a quarter counterclockwise.
+    pub fn rotated_270(&self) -> Board {
+        self.with_symmetry(3)
+    }
+
+    /// Flipped left to right.
+    pub fn mirrored_horizontal(&self) -> Board {
+        self.with_symmetry(4)
+    }
+
+    /// Flipped top to bottom.
+    pub fn mirrored_vertical(&self) -> Board {
+        self.with_symmetry(6)
+    }
+
+    /// Flipped over the diagonal from the top left corner, so rows become columns.
+    pub fn transposed(&self) -> Board {
+        self.with_symmetry(7)
+    }
+
+    /// Flipped over the diagonal from the top right corner.
+    pub fn anti_transposed(&self) -> Board {
+        self.with_symmetry(5)
+    }
+
+    /// All eight rotations and reflections, starting with the board itself. Like
+    /// the turns and diagonal flips, this panics unless the board is square.
+    pub fn symmetries(&self) -> [Board; 8] {
+        core::array::from_fn(|k| self.with_symmetry(k))
+    }
+
+    /// Parses a board like `FromStr`, also reading `theme`'s glyphs as marks.
+    pub fn parse_with_theme(s: &str, theme: Theme) -> Result<Board, ParseBoardError> {
+        let rows: Vec<Vec<char>> = s
+            .lines()
+            .map(|l| l.chars().filter(|&c| c != '|').collect())
+            .collect();
+        let size = match rows.len() {
+            1 => (1..=rows[0].len()).find(|n| n * n == rows[0].len()),
+            n => rows.iter().all(|r| r.len() == n).then_some(n),
+        };
+        let size = match size {
+            Some(n) if n > 0 => n,
+            _ => return Err(ParseBoardError::Length),
+        };
+
+        let mut marks = vec![None; size * size];
+        for (cell, c) in marks.iter_mut().zip(rows.into_iter().flatten()) {
+            *cell = match c {
+                ' ' | '.' => None,
+                _ => Some(theme.mark(c).ok_or(ParseBoardError::InvalidChar(c))?),
+            };
+        }
+
+        let count = |mark| marks.iter().filter(|&&m| m == Some(mark)).count();
+        let (x, o) = (count(Mark::X), count(Mark::O));
+        let turn = if x == o {
+            Mark::X
+        } else if x == o + 1 {
+            Mark::O
+        } else if o == x + 1 {
+            Mark::X
+        } else {
+            return Err(ParseBoardError::MarkCount);
+        };
+        let mut board = Board {
+            marks,
+            size,
+            height: size,
+            win_len: size,
+            variant: Variant::Standard,
+            gravity: false,
+            state: State::Turn(turn),
+            history: Vec::new(),
+        };
+        board.state = board.get_new_state();
+        board.validate().map_err(ParseBoardError::Illegal)?;
+        Ok(board)
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_grid(f, RenderOptions::default())
+    }
+}
+
+impl<'a> IntoIterator for &'a Board {
+    type Item = (usize, Option<Mark>);
+    type IntoIter = iter::Enumerate<iter::Copied<slice::Iter<'a, Option<Mark>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.marks.iter().copied().enumerate()
+    }
+}
+
+/// The cell at an index, as in `marks()`. Panics if it's off the board.
+impl Index<usize> for Board {
+    type Output = Option<Mark>;
+
+    fn index(&self, index: usize) -> &Option<Mark> {
+        &self.marks[index]
+    }
+}
+
+/// The cell at `(row, col)`. Panics if either is off the board, even where the
+/// flat index would land on another cell.
+impl Index<(usize, usize)> for Board {
+    type Output = Option<Mark>;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Option<Mark> {
+        assert!(
+            row < self.height && col < self.size,
+            "cell ({}, {}) is off the {}x{} board",
+            row,
+            col,
+            self.size,
+            self.height
+        );
+        &self.marks[row * self.size + col]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfRange,
+    Occupied,
+    GameOver,
+    /// With gravity, the cell below is still empty.
+    Unsupported,
+}
+
+impl fmt::Display for PlaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OutOfRange => write!(f, "that cell is off the board"),
+            Self::Occupied => write!(f, "that cell is already taken"),
+            Self::GameOver => write!(f, "the game is already over"),
+            Self::Unsupported => write!(f, "that cell has nothing under it"),
+        }
+    }
+}
+
+impl core::error::Error for PlaceError {}
+
+/// Why a move in a game between two sides couldn't be played, as `Game` and
+/// `Transcript` report it. `Board` doesn't track who is moving, so it only ever
+/// fails with the `PlaceError`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameError {
+    /// It's the other side's move.
+    NotYourTurn,
+    Place(PlaceError),
+}
+
+impl From<PlaceError> for GameError {
+    fn from(e: PlaceError) -> Self {
+        Self::Place(e)
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotYourTurn => write!(f, "it's not your turn"),
+            Self::Place(e) => e.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::NotYourTurn => None,
+            Self::Place(e) => Some(e),
+        }
+    }
+}
+
+/// Why `Board::validate` rejected a board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardError {
+    /// The cells don't fill the grid, the grid has more than `MAX_CELLS` of them,
+    /// or the win length doesn't fit on it.
+    Shape,
+    /// The mark counts can't be reached with the side to move.
+    MarkCount,
+    /// Both sides have completed a line.
+    BothWon,
+    /// With gravity, a mark sits above an empty cell.
+    Floating,
+    /// The state says the game is over when the marks don't, or the other way
+    /// round.
+    StaleState,
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Shape => write!(f, "the cells don't form a grid for this win length"),
+            Self::MarkCount => write!(f, "mark counts don't match the side to move"),
+            Self::BothWon => write!(f, "both sides have a winning line"),
+            Self::Floating => write!(f, "a mark is floating above an empty cell"),
+            Self::StaleState => write!(f, "the state doesn't match the marks"),
+        }
+    }
+}
+
+impl core::error::Error for BoardError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBoardError {
+    Length,
+    InvalidChar(char),
+    MarkCount,
+    /// The grid parsed but `Board::validate` rejected it.
+    Illegal(BoardError),
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Length => write!(f, "expected a square grid, e.g. 9 cells or 3 rows of 3"),
+            Self::InvalidChar(c) => write!(f, "invalid cell '{}', expected X, O, '.' or ' '", c),
+            Self::MarkCount => write!(f, "mark counts are impossible to reach in a game"),
+            Self::Illegal(e) => write!(f, "impossible position: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for ParseBoardError {}
+
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    /// Accepts the `Display` layout as well as a square grid written either on one
+    /// line (9 cells for 3x3) or as rows. `|` separators are ignored; `.` or a space
+    /// is an empty cell. The win length is the full width of the board.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::parse_with_theme(s, Theme::default())
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
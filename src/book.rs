@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use crate::board::{Board, State};
+use crate::minimax::{line_counts, tie_break, Limits, Memo, MiniMaxNode};
+
+// `OpeningBook::build(4)`, solved ahead of time so the first move doesn't pay for
+// it: each `encode`d canonical board with its best moves as a bit per cell. A test
+// checks it against a fresh build.
+#[rustfmt::skip]
+const STANDARD_TABLE: [(u32, u16); 54] = [
+    (0x00000, 0b111111111),
+    (0x00480, 0b111010111),
+    (0x01020, 0b100000001),
+    (0x01800, 0b100010001),
+    (0x02400, 0b100000000),
+    (0x04200, 0b101101101),
+    (0x04800, 0b100010000),
+    (0x08100, 0b101101101),
+    (0x10200, 0b011101111),
+    (0x12000, 0b000100101),
+    (0x18000, 0b000110100),
+    (0x20100, 0b011101111),
+    (0x24000, 0b000110101),
+    (0x40100, 0b101000101),
+    (0x40580, 0b101000101),
+    (0x40640, 0b111000111),
+    (0x41120, 0b100000001),
+    (0x41210, 0b010101010),
+    (0x41420, 0b000011001),
+    (0x41480, 0b100000100),
+    (0x41600, 0b110000110),
+    (0x41900, 0b000000100),
+    (0x42500, 0b000001000),
+    (0x44000, 0b101010010),
+    (0x44060, 0b100000001),
+    (0x44090, 0b000010000),
+    (0x44480, 0b100000100),
+    (0x44600, 0b101000100),
+    (0x44900, 0b000000010),
+    (0x45020, 0b100000000),
+    (0x45800, 0b100000000),
+    (0x46400, 0b000000001),
+    (0x48440, 0b000010000),
+    (0x49010, 0b000010000),
+    (0x49400, 0b000010000),
+    (0x50000, 0b000010000),
+    (0x50060, 0b000000001),
+    (0x50090, 0b000100000),
+    (0x50480, 0b000000100),
+    (0x51020, 0b010000000),
+    (0x51200, 0b010000000),
+    (0x51800, 0b010000000),
+    (0x52100, 0b000000001),
+    (0x52400, 0b000000100),
+    (0x54200, 0b001000000),
+    (0x54800, 0b001000000),
+    (0x56000, 0b000001001),
+    (0x58100, 0b000000001),
+    (0x59000, 0b000010000),
+    (0x60440, 0b000010000),
+    (0x61010, 0b000010000),
+    (0x61400, 0b000011000),
+    (0x64100, 0b000000010),
+    (0x64400, 0b000011010),
+];
+
+/// The best moves of every opening position, solved ahead of time. Positions are
+/// stored once per symmetry class, so the 3x3 book up to three marks holds 54 of
+/// them rather than 334.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpeningBook {
+    // Canonical board to every move as good as the best one, in canonical orientation.
+    moves: HashMap<Board, Vec<u8>>,
+}
+
+impl OpeningBook {
+    /// Solves every position reachable from the empty board in fewer than `plies`
+    /// moves. The searches share one memo, since each position's subtree is mostly
+    /// made of positions an earlier search already solved.
+    pub fn build(plies: usize) -> Self {
+        let mut book = OpeningBook::default();
+        let memory = RwLock::new(Memo::default());
+        let mut frontier = vec![Board::new()];
+        for _ in 0..plies {
+            let mut next = Vec::new();
+            for board in frontier {
+                if !matches!(board.state(), State::Turn(_)) {
+                    continue;
+                }
+                let key = board.canonical();
+                if book.moves.contains_key(&key) {
+                    continue;
+                }
+                let (moves, _) = MiniMaxNode::new(&key).best_moves_in(Limits::default(), &memory);
+                book.moves.insert(key.clone(), moves.unwrap_or_default());
+                for mve in key.legal_moves() {
+                    let mut child = key.clone();
+                    child.place(mve).unwrap();
+                    next.push(child);
+                }
+            }
+            frontier = next;
+        }
+        book
+    }
+
+    /// The book `best_move` consults before searching: `build(4)`, the positions
+    /// with up to three marks, read from a table built in on first use.
+    pub fn standard() -> &'static OpeningBook {
+        static BOOK: OnceLock<OpeningBook> = OnceLock::new();
+        BOOK.get_or_init(|| {
+            let moves = STANDARD_TABLE
+                .iter()
+                .map(|&(code, cells)| {
+                    let board = Board::decode(code).expect("the table holds legal positions");
+                    let moves = (0..9).filter(|i| cells >> i & 1 == 1).collect();
+                    (board, moves)
+                })
+                .collect();
+            OpeningBook { moves }
+        })
+    }
+
+    /// How many positions the book holds.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Every move as good as the best one, in board order, or `None` if the
+    /// position isn't in the book.
+    pub fn best_moves(&self, board: &Board) -> Option<Vec<u8>> {
+        let (key, perm) = board.canonical_with_symmetry();
+        let stored = self.moves.get(&key)?;
+        let moves = (0..perm.len())
+            .filter(|&i| stored.contains(&(perm[i] as u8)))
+            .map(|i| i as u8)
+            .collect();
+        Some(moves)
+    }
+
+    /// The move `MiniMaxNode::calculate` would pick, or `None` if the position
+    /// isn't in the book.
+    pub fn lookup(&self, board: &Board) -> Option<u8> {
+        let moves = self.best_moves(board)?;
+        let priority = line_counts(board);
+        moves
+            .into_iter()
+            .min_by(|&a, &b| tie_break(&priority, a, b))
+    }
+}
@@ -0,0 +1,785 @@
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use crate::board::{Board, BoardStyle, Mark, PlaceError, RenderOptions, State, Theme, Variant};
+use crate::game::{think, MAX_THINK_DELAY};
+use crate::rng::Rng;
+use crate::strategy::{MiniMaxStrategy, RandomStrategy, Strategy, SwitchingStrategy};
+use crate::{best_move, explain_move_with_theme, position_value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opponent {
+    MiniMax,
+    Random,
+    /// A random first move, then minimax; see `SwitchingStrategy::random_opening`.
+    RandomOpening,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub human: Mark,
+    pub selfplay: bool,
+    pub opponent: Opponent,
+    pub seed: Option<u64>,
+    pub alternate: bool,
+    pub color: bool,
+    pub guides: bool,
+    pub style: BoardStyle,
+    /// `--glyphs XO`: what to draw the marks as.
+    pub theme: Theme,
+    /// `--misere`: completing a line loses.
+    pub variant: Variant,
+    /// Read moves as numpad keys; see `numpad_index`.
+    pub numpad: bool,
+    /// `--think-delay-ms N`: how long the bot pauses before moving.
+    pub think_delay: Duration,
+    /// `--eval`: tell the human each turn how the game stands under perfect play.
+    pub eval: bool,
+    /// `solve FILE`: evaluate every position in the file instead of playing.
+    pub solve: Option<String>,
+    /// `replay FILE`: step through the game in a transcript file.
+    pub replay: Option<String>,
+    /// `--input FILE`: read the human's moves from the file, one per line, instead
+    /// of from stdin.
+    pub input: Option<String>,
+    pub csv: bool,
+    pub verbose: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            human: Mark::X,
+            selfplay: false,
+            opponent: Opponent::MiniMax,
+            seed: None,
+            alternate: false,
+            color: true,
+            guides: false,
+            style: BoardStyle::Plain,
+            theme: Theme::default(),
+            variant: Variant::Standard,
+            numpad: false,
+            think_delay: Duration::ZERO,
+            eval: false,
+            solve: None,
+            replay: None,
+            input: None,
+            csv: false,
+            verbose: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+        let mut options = Options::default();
+        // Read once the glyphs are known, since they may come later.
+        let mut mark = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--mark" => mark = Some(args.next().ok_or("--mark needs a value (X or O)")?),
+                "--glyphs" => {
+                    let value = args
+                        .next()
+                        .ok_or("--glyphs needs two characters, for X and O")?;
+                    options.theme = match value.chars().collect::<Vec<_>>()[..] {
+                        [first, second] if first != second => Theme { first, second },
+                        _ => {
+                            return Err(format!(
+                                "invalid glyphs '{}', expected two different characters",
+                                value
+                            ))
+                        }
+                    };
+                }
+                "--selfplay" => options.selfplay = true,
+                "--opponent" => {
+                    let value = args
+                        .next()
+                        .ok_or("--opponent needs a value (random, random-opening or minimax)")?;
+                    options.opponent = match value.as_str() {
+                        "minimax" => Opponent::MiniMax,
+                        "random" => Opponent::Random,
+                        "random-opening" => Opponent::RandomOpening,
+                        _ => {
+                            return Err(format!(
+                                "unknown opponent '{}', expected random, random-opening or minimax",
+                                value
+                            ))
+                        }
+                    };
+                }
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a number")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?;
+                    options.seed = Some(seed);
+                }
+                "--alternate" => options.alternate = true,
+                "--think-delay-ms" => {
+                    let value = args.next().ok_or("--think-delay-ms needs a number")?;
+                    let ms = value
+                        .parse()
+                        .map_err(|_| format!("invalid delay '{}'", value))?;
+                    options.think_delay = Duration::from_millis(ms).min(MAX_THINK_DELAY);
+                }
+                "--no-color" => options.color = false,
+                "--guides" => options.guides = true,
+                "--numpad" => options.numpad = true,
+                "--eval" => options.eval = true,
+                "--misere" => options.variant = Variant::Misere,
+                "--oneline" => options.style = BoardStyle::OneLine,
+                "--style" => {
+                    let value = args
+                        .next()
+                        .ok_or("--style needs a value (plain, boxed, compact or oneline)")?;
+                    options.style = match value.as_str() {
+                        "plain" => BoardStyle::Plain,
+                        "boxed" => BoardStyle::Boxed,
+                        "compact" => BoardStyle::Compact,
+                        "oneline" => BoardStyle::OneLine,
+                        _ => {
+                            return Err(format!(
+                                "unknown style '{}', expected plain, boxed, compact or oneline",
+                                value
+                            ))
+                        }
+                    };
+                }
+                "--input" => {
+                    let path = args.next().ok_or("--input needs a file of moves")?;
+                    options.input = Some(path);
+                }
+                "solve" => {
+                    let path = args.next().ok_or("solve needs a file of positions")?;
+                    options.solve = Some(path);
+                }
+                "replay" => {
+                    let path = args.next().ok_or("replay needs a transcript file")?;
+                    options.replay = Some(path);
+                }
+                "--csv" => options.csv = true,
+                "--verbose" => options.verbose = true,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+        if let Some(value) = mark {
+            let mut chars = value.chars();
+            options.human = match (
+                chars.next().and_then(|c| options.theme.mark(c)),
+                chars.next(),
+            ) {
+                (Some(m), None) => m,
+                _ => return Err(format!("unknown mark '{}', expected X or O", value)),
+            };
+        }
+        Ok(options)
+    }
+
+    /// The bot `--opponent` and `--seed` ask for. Without a seed the minimax bot
+    /// breaks ties the way `best_move` does, and the others draw one from the OS.
+    pub fn bot(&self) -> Box<dyn Strategy> {
+        let seed = || self.seed.unwrap_or_else(|| Rng::from_entropy().next_u64());
+        match self.opponent {
+            Opponent::MiniMax => match self.seed {
+                Some(seed) => Box::new(MiniMaxStrategy::seeded(seed)),
+                None => Box::new(MiniMaxStrategy::new()),
+            },
+            Opponent::Random => Box::new(RandomStrategy::new(seed())),
+            Opponent::RandomOpening => Box::new(SwitchingStrategy::random_opening(seed())),
+        }
+    }
+}
+
+/// Whether to color the board: only if `--no-color` wasn't given and the
+/// `NO_COLOR` environment variable, passed in as `no_color`, is unset or empty.
+pub fn use_color(options: &Options, no_color: Option<&OsStr>) -> bool {
+    options.color && no_color.is_none_or(|v| v.is_empty())
+}
+
+/// How the game is shown to the player and how their moves are read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Screen {
+    pub render: RenderOptions,
+    /// Whether the output is an interactive terminal; see `clear_screen`.
+    pub is_terminal: bool,
+    /// Moves are typed as numpad keys instead of indices.
+    pub numpad: bool,
+    /// How long the bot pauses before its move; zero for none.
+    pub think_delay: Duration,
+    /// Show the `Verdict` on the human's position with every prompt.
+    pub eval: bool,
+}
+
+impl Screen {
+    /// A board index the way the player types it: the numpad key in numpad mode.
+    pub fn move_name(&self, index: usize) -> String {
+        match self.numpad.then(|| numpad_key(index)).flatten() {
+            Some(key) => key.to_string(),
+            None => index.to_string(),
+        }
+    }
+}
+
+/// How a game in progress ends under perfect play, for the player to move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Verdict {
+    /// The verdict from `position_value`, or `None` once the game is over.
+    pub fn of(board: &Board) -> Option<Verdict> {
+        let mover = board.current_player()?;
+        let value = position_value(board) * mover.to_value() as i32;
+        Some(match value.signum() {
+            1 => Verdict::Win,
+            0 => Verdict::Draw,
+            _ => Verdict::Loss,
+        })
+    }
+}
+
+/// What the bot tells the human it sees when it's their move, so "you" is the
+/// player to move.
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Win => write!(f, "Bot sees: You are winning."),
+            Verdict::Draw => write!(f, "Bot sees: Draw with best play."),
+            Verdict::Loss => write!(f, "Bot sees: You are losing."),
+        }
+    }
+}
+
+/// What `play_game` leaves behind: the board, and how the game ended, which is
+/// `None` if `input` ran out first. A resignation counts as a win for the other
+/// side and an agreed draw as a tie, whatever the board says.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayedGame {
+    pub board: Board,
+    pub result: Option<State>,
+}
+
+/// Plays one game between the human, typing moves on `input`, and `bot`, until the
+/// game is over, the human resigns or has a draw offer accepted, or `input` runs
+/// out. The state is checked before every move, so nobody moves once the game is
+/// won or tied.
+pub fn play_game(
+    mut board: Board,
+    human: Mark,
+    bot: &mut dyn Strategy,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    screen: Screen,
+) -> io::Result<PlayedGame> {
+    let glyph = |m: Mark| screen.render.theme.glyph(m);
+    let mut notice = None;
+    // The loop only ends once the game has a result: the winner if there is one,
+    // and how it ended if that wasn't on the board.
+    let (winner, ending) = loop {
+        let turn = match *board.state() {
+            State::Turn(turn) => turn,
+            State::Won(m) => break (Some(m), None),
+            State::Tie => break (None, None),
+        };
+        if turn != human {
+            writeln!(out, "Bot's turn ({})", glyph(turn))?;
+            let mve = bot.choose(&board);
+            think(screen.think_delay);
+            notice = Some(format!(
+                "Bot played {}: {}",
+                screen.move_name(mve),
+                explain_move_with_theme(&board, mve, screen.render.theme)
+            ));
+            board.place(mve).unwrap();
+            continue;
+        }
+        match read_move_after(&mut board, human, input, out, screen, notice.take())? {
+            None => {
+                writeln!(out, "No more input, exiting.")?;
+                return Ok(PlayedGame {
+                    board,
+                    result: None,
+                });
+            }
+            Some(Response::Resign) => break (Some(human.other()), Some("You resigned.")),
+            Some(Response::OfferDraw) if bot.accepts_draw(&board) => {
+                break (None, Some("The bot accepts the draw."))
+            }
+            Some(Response::OfferDraw) => notice = Some("The bot declines the draw.".into()),
+            Some(_) => {}
+        }
+    };
+
+    clear_screen(out, screen.is_terminal)?;
+    write!(out, "{}", board.render(screen.render))?;
+    if let Some(ending) = ending {
+        writeln!(out, "{}", ending)?;
+    }
+    match winner {
+        Some(m) if m == human => writeln!(out, "{} Won! You beat the bot.", glyph(m))?,
+        Some(m) => writeln!(out, "{} Won! The bot wins.", glyph(m))?,
+        None => writeln!(out, "Tie!")?,
+    }
+    writeln!(out, "{}", Summary::new(&board, human).render(screen))?;
+    Ok(PlayedGame {
+        board,
+        result: Some(winner.map_or(State::Tie, State::Won)),
+    })
+}
+
+/// How a game went: how it ended, after how many moves, and which of the human's
+/// moves gave away a better result than the engine's move would have kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub state: State,
+    pub moves: usize,
+    pub mistakes: Vec<Mistake>,
+}
+
+/// A move that turned a win into a draw or loss, or a draw into a loss.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mistake {
+    /// Counting from 1 for the first move of the game.
+    pub move_number: usize,
+    pub played: usize,
+    /// What `best_move` would have played instead.
+    pub best: u8,
+}
+
+impl Summary {
+    /// Looks back over `board`'s history, checking each of `human`'s moves against
+    /// `best_move` in the position it was played from. Only the result under
+    /// perfect play counts, so a slower win than the engine's isn't a mistake.
+    pub fn new(board: &Board, human: Mark) -> Summary {
+        // From the human's side: 1 for a win, 0 for a draw and -1 for a loss.
+        let outcome = |board: &Board, mve: usize| {
+            let mut child = board.clone();
+            child.place(mve).unwrap();
+            let value = position_value(&child).signum();
+            match human {
+                Mark::X => value,
+                Mark::O => -value,
+            }
+        };
+        let mut mistakes = Vec::new();
+        let mut before = board.clone();
+        while let Some(played) = before.undo() {
+            if before.current_player() != Some(human) {
+                continue;
+            }
+            let best = best_move(&before).unwrap();
+            if outcome(&before, played) < outcome(&before, best as usize) {
+                mistakes.push(Mistake {
+                    move_number: before.move_count() + 1,
+                    played,
+                    best,
+                });
+            }
+        }
+        mistakes.reverse();
+        Summary {
+            state: board.state().clone(),
+            moves: board.move_count(),
+            mistakes,
+        }
+    }
+
+    /// The summary with moves named the way `screen` reads them; see
+    /// `Screen::move_name`. `Display` names them by index.
+    pub fn render(&self, screen: Screen) -> String {
+        // `play_game` has already announced the result, so this only adds to it.
+        let mut text = format!("Game over after {} moves.", self.moves);
+        if self.mistakes.is_empty() {
+            text.push_str(" You played optimally throughout.");
+        }
+        for mistake in &self.mistakes {
+            text.push_str(&format!(
+                "\nMove {}: you played {}, but {} was better.",
+                mistake.move_number,
+                screen.move_name(mistake.played),
+                screen.move_name(mistake.best.into())
+            ));
+        }
+        text
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Screen::default()))
+    }
+}
+
+/// The most bytes of a line `read_input_line` keeps. Every answer the CLI takes is
+/// a few characters, so a longer line is skipped rather than held in memory.
+pub const MAX_LINE_LEN: usize = 64;
+
+/// How many invalid answers in a row `read_move` takes before giving up.
+pub const MAX_INVALID_INPUTS: usize = 10;
+
+/// A line read by `read_input_line`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputLine {
+    /// The line without its `\n`, with any invalid UTF-8 replaced by `U+FFFD`.
+    Text(String),
+    /// Longer than `MAX_LINE_LEN` bytes, so it was read to the end but not kept.
+    TooLong,
+}
+
+/// Reads the next line of `input`, holding on to at most `MAX_LINE_LEN` bytes of
+/// it however long it is. `None` once `input` has run out.
+pub fn read_input_line(input: &mut impl BufRead) -> io::Result<Option<InputLine>> {
+    let mut line = Vec::new();
+    let mut too_long = false;
+    let mut read_any = false;
+    loop {
+        let buf = match input.fill_buf() {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if buf.is_empty() {
+            break;
+        }
+        read_any = true;
+        let (chunk, used, done) = match buf.iter().position(|&b| b == b'\n') {
+            Some(end) => (&buf[..end], end + 1, true),
+            None => (buf, buf.len(), false),
+        };
+        if line.len() + chunk.len() > MAX_LINE_LEN {
+            too_long = true;
+        } else if !too_long {
+            line.extend_from_slice(chunk);
+        }
+        input.consume(used);
+        if done {
+            break;
+        }
+    }
+    Ok(match (read_any, too_long) {
+        (false, _) => None,
+        (true, true) => Some(InputLine::TooLong),
+        (true, false) => Some(InputLine::Text(String::from_utf8_lossy(&line).into_owned())),
+    })
+}
+
+/// Prompts the human until they play a move on `board`, resign or offer a draw,
+/// answering hints along the way, and returns which it was: `Placed`, `Resign` or
+/// `OfferDraw`. `None` means `input` ran out first. After `MAX_INVALID_INPUTS`
+/// unusable answers in a row it gives up with an `InvalidInput` error.
+pub fn read_move(
+    board: &mut Board,
+    human: Mark,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    screen: Screen,
+) -> io::Result<Option<Response>> {
+    read_move_after(board, human, input, out, screen, None)
+}
+
+// `read_move`, showing `notice` under the board the first time it is drawn.
+fn read_move_after(
+    board: &mut Board,
+    human: Mark,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    screen: Screen,
+    mut notice: Option<String>,
+) -> io::Result<Option<Response>> {
+    let verdict = screen.eval.then(|| Verdict::of(board)).flatten();
+    let mut invalid = 0;
+    loop {
+        clear_screen(out, screen.is_terminal)?;
+        let prompt = match screen.numpad {
+            true => "Enter your move as a numpad key (7 8 9 is the top row), hint, resign or draw",
+            false => "Enter your move as a board index (0..=8), a row and column (1 2), hint, resign or draw",
+        };
+        writeln!(
+            out,
+            "{}\nYour turn ({})",
+            prompt,
+            screen.render.theme.glyph(human)
+        )?;
+        write!(out, "{}", board.render(screen.render))?;
+        if board.is_forced_draw() {
+            writeln!(out, "This game is a forced draw.")?;
+        } else if !board.can_win(human) {
+            writeln!(out, "You can no longer win; playing for a draw.")?;
+        }
+        if let Some(verdict) = verdict {
+            writeln!(out, "{}", verdict)?;
+        }
+        if let Some(notice) = notice.take() {
+            writeln!(out, "{}", notice)?;
+        }
+        out.flush()?;
+        let line = match read_input_line(input)? {
+            None => return Ok(None),
+            Some(InputLine::TooLong) => None,
+            Some(InputLine::Text(line)) => Some(line),
+        };
+        let response = line.map(|line| match screen.numpad {
+            true => handle_numpad_input(board, &line),
+            false => handle_input(board, &line),
+        });
+        match response {
+            Some(Response::Placed(_) | Response::Resign | Response::OfferDraw) => {
+                return Ok(response)
+            }
+            Some(Response::Hint(mve)) => {
+                notice = Some(format!("Hint: play {}.", screen.move_name(mve.into())));
+                continue;
+            }
+            None => notice = Some("That line is too long to be a move.".into()),
+            Some(Response::Unrecognized) if screen.numpad => {
+                notice = Some("Enter a numpad key from 1 to 9, or hint.".into())
+            }
+            Some(Response::Unrecognized) => {
+                notice =
+                    Some("Enter an index like 4, a row and column like 1 2 or 1,2, or hint.".into())
+            }
+            Some(Response::Invalid(e)) => notice = Some(format!("Invalid move: {}.", e)),
+        }
+        // Hints don't count; anything else that isn't a move does.
+        invalid += 1;
+        if invalid == MAX_INVALID_INPUTS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} invalid answers in a row", invalid),
+            ));
+        }
+    }
+}
+
+/// Shows `boards` one at a time, as from `Transcript::positions`. Enter steps
+/// forward and ends the replay after the last board, `b` steps back and `q` quits,
+/// as does running out of input.
+pub fn step_through(
+    boards: &[Board],
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    screen: Screen,
+) -> io::Result<()> {
+    let last = boards.len().saturating_sub(1);
+    let mut step = 0;
+    let mut notice = None;
+    while let Some(board) = boards.get(step) {
+        clear_screen(out, screen.is_terminal)?;
+        writeln!(out, "Move {} of {}", step, last)?;
+        write!(out, "{}", board.render(screen.render))?;
+        match board.state() {
+            State::Won(m) => writeln!(out, "{} Won!", screen.render.theme.glyph(*m))?,
+            State::Tie => writeln!(out, "Tie!")?,
+            State::Turn(_) => {}
+        }
+        if let Some(notice) = notice.take() {
+            writeln!(out, "{}", notice)?;
+        }
+        writeln!(out, "Enter: next, b: back, q: quit")?;
+        out.flush()?;
+        let line = match read_input_line(input)? {
+            None => break,
+            Some(InputLine::Text(line)) => line,
+            Some(InputLine::TooLong) => {
+                notice = Some("Press Enter, b or q.");
+                continue;
+            }
+        };
+        match line.trim() {
+            "" => step += 1,
+            "b" | "B" => step = step.saturating_sub(1),
+            "q" | "Q" => break,
+            _ => notice = Some("Press Enter, b or q."),
+        }
+    }
+    Ok(())
+}
+
+/// Clears the screen and homes the cursor on a terminal. Anywhere else the
+/// escapes would only garble the output, so this just starts a new line.
+pub fn clear_screen(out: &mut impl Write, is_terminal: bool) -> io::Result<()> {
+    if is_terminal {
+        write!(out, "\x1B[2J\x1B[1;1H")
+    } else {
+        writeln!(out)
+    }
+}
+
+/// Wins, losses and ties over a session of several games.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Scoreboard {
+    pub human: u32,
+    pub bot: u32,
+    pub ties: u32,
+}
+
+impl Scoreboard {
+    /// Counts a finished game. Games still in progress are ignored.
+    pub fn record(&mut self, state: &State, human: Mark) {
+        match state {
+            State::Won(m) if *m == human => self.human += 1,
+            State::Won(_) => self.bot += 1,
+            State::Tie => self.ties += 1,
+            State::Turn(_) => {}
+        }
+    }
+}
+
+impl fmt::Display for Scoreboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "You: {}  Bot: {}  Ties: {}",
+            self.human, self.bot, self.ties
+        )
+    }
+}
+
+/// What came of a line typed at the move prompt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    /// The move at this index was played.
+    Placed(usize),
+    /// The player asked for a hint; this is the engine's move for them. Nothing was played.
+    Hint(u8),
+    /// A board index that can't be played right now.
+    Invalid(PlaceError),
+    /// The player typed `resign`. Nothing was played.
+    Resign,
+    /// The player typed `draw` to offer one. Nothing was played.
+    OfferDraw,
+    /// Not an index, a row and column, or one of the words above.
+    Unrecognized,
+}
+
+/// Plays the index typed at the prompt, or answers `hint` with the engine's
+/// suggestion for the side to move without changing the board.
+pub fn handle_input(board: &mut Board, input: &str) -> Response {
+    let size = board.size();
+    respond(board, input, |input| parse_move_on(input, size))
+}
+
+/// Like `handle_input`, but the move is a numpad key; see `numpad_index`.
+pub fn handle_numpad_input(board: &mut Board, input: &str) -> Response {
+    respond(board, input, |input| {
+        let mut chars = input.chars();
+        match (chars.next(), chars.next()) {
+            (Some(key), None) => numpad_index(key),
+            _ => None,
+        }
+    })
+}
+
+fn respond(board: &mut Board, input: &str, parse: impl Fn(&str) -> Option<usize>) -> Response {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("resign") {
+        return Response::Resign;
+    }
+    if input.eq_ignore_ascii_case("draw") {
+        return Response::OfferDraw;
+    }
+    if input.eq_ignore_ascii_case("hint") {
+        return match best_move(board) {
+            Some(mve) => Response::Hint(mve),
+            None => Response::Invalid(PlaceError::GameOver),
+        };
+    }
+    match parse(input) {
+        Some(index) => match board.place(index) {
+            Ok(()) => Response::Placed(index),
+            Err(e) => Response::Invalid(e),
+        },
+        None => Response::Unrecognized,
+    }
+}
+
+/// Reads a move for the 3x3 board, either as a bare index (`4`) or as a zero-based
+/// row and column separated by a space or comma (`1 2`, `2,0`).
+pub fn parse_move(input: &str) -> Option<usize> {
+    parse_move_on(input, 3)
+}
+
+/// The 3x3 board index for a numpad key, where 7 8 9 is the top row and 1 2 3 the
+/// bottom one. `None` for anything but the digits 1 to 9.
+pub fn numpad_index(key: char) -> Option<usize> {
+    let key = key.to_digit(10).filter(|&k| k >= 1)? as usize - 1;
+    let (row, col) = (2 - key / 3, key % 3);
+    Some(row * 3 + col)
+}
+
+/// The numpad key for a 3x3 board index, undoing `numpad_index`. `None` off the
+/// board.
+pub fn numpad_key(index: usize) -> Option<char> {
+    let (row, col) = (index / 3, index % 3);
+    let key = (index < 9).then(|| (2 - row) * 3 + col + 1)?;
+    char::from_digit(key as u32, 10)
+}
+
+fn parse_move_on(input: &str, size: usize) -> Option<usize> {
+    let input = input.trim();
+    if let Ok(index) = input.parse() {
+        return Some(index);
+    }
+    let input = input.replace(',', " ");
+    let mut parts = input.split_whitespace().map(|p| p.parse::<usize>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(row)), Some(Ok(col)), None) if row < size && col < size => Some(row * size + col),
+        _ => None,
+    }
+}
+
+/// Reads one position per line in the `FromStr` format and writes each board with
+/// its value and best move, either as text or as CSV rows of
+/// `line,board,value,best_move`. Lines that don't parse are reported to `errors`
+/// with their line number and skipped; blank lines are ignored.
+pub fn solve_positions(
+    input: impl BufRead,
+    out: &mut impl Write,
+    errors: &mut impl Write,
+    csv: bool,
+) -> io::Result<()> {
+    if csv {
+        writeln!(out, "line,board,value,best_move")?;
+    }
+    for (number, line) in input.lines().enumerate() {
+        let line = line?;
+        let number = number + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let board: Board = match line.trim().parse() {
+            Ok(board) => board,
+            Err(e) => {
+                writeln!(errors, "line {}: {}", number, e)?;
+                continue;
+            }
+        };
+        let value = position_value(&board);
+        let mve = best_move(&board);
+        if csv {
+            let cells: String = board
+                .marks()
+                .iter()
+                .map(|m| m.map_or('.', |m| m.to_char()))
+                .collect();
+            let mve = mve.map_or(String::new(), |m| m.to_string());
+            writeln!(out, "{},{},{},{}", number, cells, value, mve)?;
+        } else {
+            write!(out, "{}", board)?;
+            match mve {
+                Some(mve) => writeln!(out, "Value: {}  Best move: {}", value, mve)?,
+                None => writeln!(out, "Value: {}  Game over", value)?,
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::sync::RwLock;
+
+use crate::board::{Board, State};
+use crate::minimax::{Memo, MiniMaxNode, SearchStats};
+
+/// A search that keeps its memo between calls, so each move of a game starts from
+/// the positions solved for the earlier ones. Stored positions are absolute, so the
+/// memo stays valid whatever board it's asked about next.
+///
+/// The memo sits behind a lock, so one engine can be shared between threads and
+/// asked about different games at the same time.
+#[derive(Debug, Default)]
+pub struct Engine {
+    memo: RwLock<Memo>,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from the positions already solved in `memo`.
+    pub fn with_memo(memo: Memo) -> Self {
+        Self {
+            memo: RwLock::new(memo),
+        }
+    }
+
+    /// A copy of the positions solved so far.
+    pub fn memo(&self) -> Memo {
+        self.memo.read().unwrap().clone()
+    }
+
+    pub fn into_memo(self) -> Memo {
+        self.memo.into_inner().unwrap()
+    }
+
+    /// Forgets every solved position.
+    pub fn clear(&self) {
+        *self.memo.write().unwrap() = Memo::new();
+    }
+
+    /// The same move as `best_move`, or `None` if the game is over.
+    pub fn best_move(&self, board: &Board) -> Option<u8> {
+        self.best_move_with_stats(board).map(|(mve, _)| mve)
+    }
+
+    /// Like `best_move`, but also reports how much work the search did.
+    pub fn best_move_with_stats(&self, board: &Board) -> Option<(u8, SearchStats)> {
+        match board.state() {
+            State::Turn(_) => Some(MiniMaxNode::new(board).calculate_shared(&self.memo)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use crate::board::{Board, HeuristicWeights};
+
+/// Scores positions where a depth-limited search stops looking, from X's point of
+/// view. Finished games never reach it; the search scores those itself. `Sync` so
+/// the parallel search can share one. Scores needn't be the same for a position's
+/// rotations and mirror images: with a custom evaluator the search remembers each
+/// orientation separately.
+pub trait Evaluator: Sync {
+    fn score(&self, board: &Board) -> i32;
+}
+
+/// The default heuristic, `Board::evaluate`: open lines count for the side that
+/// can still complete them, twice over when one more mark would do it. The same as
+/// the default `HeuristicWeights`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TwoInARowEvaluator;
+
+impl Evaluator for TwoInARowEvaluator {
+    fn score(&self, board: &Board) -> i32 {
+        board.evaluate()
+    }
+}
+
+/// `Board::evaluate_with` these weights.
+impl Evaluator for HeuristicWeights {
+    fn score(&self, board: &Board) -> i32 {
+        board.evaluate_with(self)
+    }
+}
@@ -0,0 +1,115 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::board::{Board, Mark, Theme, Variant};
+
+/// A sentence saying why `mv` is a sensible move for the side to play on `board`:
+/// it wins, blocks a line the opponent was about to complete, sets up two threats
+/// at once, takes a cell the opponent could fork from, or else just claims the
+/// center, a corner or an edge. Under `Variant::Misere`, where completing a line
+/// loses, only that and the kind of cell are said. Panics unless `mv` is a legal
+/// move.
+pub fn explain_move(board: &Board, mv: usize) -> String {
+    explain_move_with_theme(board, mv, Theme::default())
+}
+
+/// Like `explain_move`, but names the opponent with `theme`'s glyph.
+pub fn explain_move_with_theme(board: &Board, mv: usize, theme: Theme) -> String {
+    let me = board.current_player().expect("the game is over");
+    let them = me.other();
+    let place = cell_kind(board, mv);
+    let completes = |mark| board.threats(mark).into_iter().find(|l| l.contains(&mv));
+
+    if board.variant() == Variant::Misere {
+        return match completes(me) {
+            Some(line) => format!("Completing the {}, which loses.", line_name(board, &line)),
+            None => format!("Taking {}.", place),
+        };
+    }
+    if let Some(line) = completes(me) {
+        return format!("Completing the {} to win.", line_name(board, &line));
+    }
+    if let Some(line) = completes(them) {
+        return format!(
+            "Blocking {}'s threat on the {}.",
+            theme.glyph(them),
+            line_name(board, &line)
+        );
+    }
+    let mut after = board.clone();
+    after.place(mv).expect("not a legal move");
+    if winning_cells(&after, me).len() >= 2 {
+        return format!("Taking {} to create a double threat.", place);
+    }
+    if forks(board, them).contains(&mv) {
+        return format!(
+            "Taking {}, where {} could have made a double threat.",
+            place,
+            theme.glyph(them)
+        );
+    }
+    format!("Taking {}.", place)
+}
+
+// The empty cells `mark` could complete a line on, each once.
+fn winning_cells(board: &Board, mark: Mark) -> Vec<usize> {
+    let mut cells: Vec<usize> = board
+        .threats(mark)
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|&i| board.marks()[i].is_none())
+        .collect();
+    cells.sort_unstable();
+    cells.dedup();
+    cells
+}
+
+// The cells where `mark`, if it were their move, would make two threats at once.
+fn forks(board: &Board, mark: Mark) -> Vec<usize> {
+    board
+        .legal_moves()
+        .filter(|&i| {
+            let mut after = board.clone();
+            after.marks[i] = Some(mark);
+            winning_cells(&after, mark).len() >= 2
+        })
+        .collect()
+}
+
+// "the center", "a corner" or "an edge", as the object of "Taking".
+fn cell_kind(board: &Board, index: usize) -> &'static str {
+    let (w, h) = (board.size(), board.height());
+    let (row, col) = (index / w, index % w);
+    let (top_or_bottom, left_or_right) = (row == 0 || row + 1 == h, col == 0 || col + 1 == w);
+    if w % 2 == 1 && h % 2 == 1 && row == h / 2 && col == w / 2 {
+        "the center"
+    } else if top_or_bottom && left_or_right {
+        "a corner"
+    } else if top_or_bottom || left_or_right {
+        "an edge"
+    } else {
+        "an inner cell"
+    }
+}
+
+// What the line through `cells` is called: "top row", "left column", "diagonal"
+// and so on on 3x3, and by number on bigger boards.
+fn line_name(board: &Board, cells: &[usize]) -> String {
+    let w = board.size();
+    let (row, col) = (cells[0] / w, cells[0] % w);
+    let step = match cells {
+        [a, b, ..] => b - a,
+        _ => 1,
+    };
+    let standard = w == 3 && board.height() == 3;
+    match step {
+        1 if standard => ["top row", "middle row", "bottom row"][row].into(),
+        1 => format!("row {}", row + 1),
+        s if s == w && standard => ["left column", "middle column", "right column"][col].into(),
+        s if s == w => format!("column {}", col + 1),
+        s if s == w + 1 => "diagonal from the top left".into(),
+        _ => "diagonal from the top right".into(),
+    }
+}
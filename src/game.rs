@@ -0,0 +1,97 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::board::{Board, GameError, Mark, State};
+use crate::strategy::Strategy;
+
+/// The longest the bot will pretend to think, whatever it is asked for.
+pub const MAX_THINK_DELAY: Duration = Duration::from_secs(5);
+
+/// A game between a human and a bot: the board, the moves so far, which side the
+/// human plays and how the bot picks its moves. `Board` only knows the rules; this
+/// is the driver that takes turns.
+pub struct Game {
+    board: Board,
+    human: Mark,
+    opponent: Box<dyn Strategy>,
+    think_delay: Duration,
+}
+
+impl Game {
+    /// Starts from an empty board with X to move.
+    pub fn new(human: Mark, opponent: Box<dyn Strategy>) -> Self {
+        Self::from_board(Board::new(), human, opponent)
+    }
+
+    pub fn from_board(board: Board, human: Mark, opponent: Box<dyn Strategy>) -> Self {
+        Game {
+            board,
+            human,
+            opponent,
+            think_delay: Duration::ZERO,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The cells played so far, in order; see `Board::history`.
+    pub fn history(&self) -> &[usize] {
+        self.board.history()
+    }
+
+    pub fn human(&self) -> Mark {
+        self.human
+    }
+
+    pub fn think_delay(&self) -> Duration {
+        self.think_delay
+    }
+
+    /// Makes `ai_move` pause this long before moving, so the bot doesn't answer
+    /// instantly. Zero, the default, skips the pause; anything over
+    /// `MAX_THINK_DELAY` is cut down to it.
+    pub fn set_think_delay(&mut self, delay: Duration) {
+        self.think_delay = delay.min(MAX_THINK_DELAY);
+    }
+
+    /// Plays `index` for the human. Fails with `NotYourTurn` while the bot is to move.
+    pub fn human_move(&mut self, index: usize) -> Result<(), GameError> {
+        if self.board.current_player() == Some(self.human.other()) {
+            return Err(GameError::NotYourTurn);
+        }
+        Ok(self.board.place(index)?)
+    }
+
+    /// Lets the bot move and returns the cell it played, or `None` if it isn't the
+    /// bot's turn.
+    pub fn ai_move(&mut self) -> Option<usize> {
+        if self.board.current_player() != Some(self.human.other()) {
+            return None;
+        }
+        let index = self.opponent.choose(&self.board);
+        think(self.think_delay);
+        self.board.place(index).unwrap();
+        Some(index)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.board.is_over()
+    }
+
+    /// How the game ended, or `None` while it is still going.
+    pub fn result(&self) -> Option<State> {
+        match self.board.state() {
+            State::Turn(_) => None,
+            state => Some(state.clone()),
+        }
+    }
+}
+
+/// Waits out a think delay. Zero returns at once.
+pub(crate) fn think(delay: Duration) {
+    if !delay.is_zero() {
+        thread::sleep(delay);
+    }
+}
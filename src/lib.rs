@@ -0,0 +1,274 @@
+//! Without the default `std` feature only the rules in `Board`, game
+//! `Transcript`s and `explain_move` are built, on `core` and `alloc`; the search,
+//! strategies and CLI need `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "serde")]
+use std::fs::File;
+#[cfg(feature = "serde")]
+use std::io::{self, BufReader, BufWriter};
+#[cfg(feature = "serde")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+mod board;
+#[cfg(feature = "std")]
+mod book;
+#[cfg(feature = "std")]
+pub mod cli;
+#[cfg(feature = "std")]
+mod engine;
+#[cfg(feature = "std")]
+mod evaluator;
+mod explain;
+#[cfg(feature = "std")]
+mod game;
+#[cfg(feature = "std")]
+mod minimax;
+#[cfg(feature = "std")]
+mod rng;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "std")]
+mod strategy;
+#[cfg(feature = "std")]
+mod tablebase;
+#[cfg(feature = "std")]
+mod tournament;
+mod transcript;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use board::{
+    Board, BoardError, BoardStyle, GameError, HeuristicWeights, Mark, Outcomes, ParseBoardError,
+    PlaceError, RenderOptions, State, Theme, Variant, MAX_CELLS,
+};
+#[cfg(feature = "std")]
+pub use book::OpeningBook;
+#[cfg(feature = "std")]
+pub use engine::Engine;
+#[cfg(feature = "std")]
+pub use evaluator::{Evaluator, TwoInARowEvaluator};
+pub use explain::{explain_move, explain_move_with_theme};
+#[cfg(feature = "std")]
+pub use game::{Game, MAX_THINK_DELAY};
+#[cfg(feature = "std")]
+use minimax::Limits;
+#[cfg(feature = "std")]
+pub use minimax::{Memo, MiniMaxNode, SearchStats};
+#[cfg(feature = "std")]
+pub use rng::Rng;
+#[cfg(feature = "std")]
+pub use strategy::{MiniMaxStrategy, RandomStrategy, Strategy, SwitchingStrategy};
+#[cfg(feature = "std")]
+pub use tablebase::{Outcome, Tablebase};
+#[cfg(feature = "std")]
+pub use tournament::{round_robin, Record, Standings};
+pub use transcript::{ParseTranscriptError, ReplayError, Transcript};
+
+/// What `best_move` plays on an empty standard board: the center, as a full search
+/// would also pick.
+#[cfg(feature = "std")]
+const FIRST_MOVE: u8 = 4;
+
+/// What `best_move` answers each first move on the standard board with, by the
+/// cell that was opened: a corner against the center and the center against
+/// anything else, as opening theory has it.
+#[cfg(feature = "std")]
+const OPENING_REPLIES: [u8; 9] = [4, 4, 4, 4, 0, 4, 4, 4, 4];
+
+/// Returns the engine's move for the player whose turn it is, or `None` if the game is over.
+/// The first two moves of a standard game come from fixed tables and the rest of
+/// the opening from the opening book, all without searching.
+#[cfg(feature = "std")]
+pub fn best_move(board: &Board) -> Option<u8> {
+    match *board.state() {
+        State::Turn(m) if *board == Board::with_first_player(m) => Some(FIRST_MOVE),
+        State::Turn(m) => opening_reply(board, m)
+            .or_else(|| OpeningBook::standard().lookup(board))
+            .or_else(|| Some(MiniMaxNode::new(board).calculate())),
+        _ => None,
+    }
+}
+
+// The `OPENING_REPLIES` entry if `board` is a standard board `m`'s opponent has
+// made the only move on.
+#[cfg(feature = "std")]
+fn opening_reply(board: &Board, m: Mark) -> Option<u8> {
+    let (opened, _) = board.cells().find(|(_, cell)| cell.is_some())?;
+    let mut expected = Board::with_first_player(m.other());
+    expected.place(opened).ok()?;
+    (expected == *board).then(|| OPENING_REPLIES[opened])
+}
+
+/// Like `best_move`, but only searches `depth` moves ahead.
+#[cfg(feature = "std")]
+pub fn best_move_with_depth(board: &Board, depth: usize) -> Option<u8> {
+    match board.state() {
+        State::Turn(_) => Some(MiniMaxNode::new(board).calculate_with_depth(depth)),
+        _ => None,
+    }
+}
+
+/// Like `best_move_with_depth`, but scores the positions at the cutoff with `evaluator`.
+#[cfg(feature = "std")]
+pub fn best_move_with_evaluator(
+    board: &Board,
+    depth: usize,
+    evaluator: &dyn Evaluator,
+) -> Option<u8> {
+    match board.state() {
+        State::Turn(_) => Some(MiniMaxNode::new(board).calculate_with_evaluator(depth, evaluator)),
+        _ => None,
+    }
+}
+
+/// The score of `board` under perfect play from here, from X's point of view:
+/// positive if X wins, negative if O wins and zero for a draw. Quicker wins score
+/// higher. Finished games get the score of their result.
+#[cfg(feature = "std")]
+pub fn position_value(board: &Board) -> i32 {
+    MiniMaxNode::new(board).calculate_value()
+}
+
+/// Like `best_move`, but also reports how much work the search did.
+#[cfg(feature = "std")]
+pub fn best_move_with_stats(board: &Board) -> Option<(u8, SearchStats)> {
+    match board.state() {
+        State::Turn(_) => Some(MiniMaxNode::new(board).calculate_with_stats()),
+        _ => None,
+    }
+}
+
+/// Like `best_move_with_stats`, but warm-starts from the positions in `memo` and
+/// leaves the newly solved ones in it.
+#[cfg(feature = "std")]
+pub fn best_move_with_memo(board: &Board, memo: &mut Memo) -> Option<(u8, SearchStats)> {
+    match board.state() {
+        State::Turn(_) => Some(MiniMaxNode::new(board).calculate_with_memo(memo)),
+        _ => None,
+    }
+}
+
+/// Writes `memo` to `path` as JSON.
+#[cfg(feature = "serde")]
+pub fn save_memo(memo: &Memo, path: impl AsRef<Path>) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(file, memo)?;
+    Ok(())
+}
+
+/// Reads a memo written by `save_memo`.
+#[cfg(feature = "serde")]
+pub fn load_memo(path: impl AsRef<Path>) -> io::Result<Memo> {
+    let file = BufReader::new(File::open(path)?);
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// Like `best_move`, but searches each possible move on its own thread.
+#[cfg(feature = "std")]
+pub fn best_move_parallel(board: &Board) -> Option<u8> {
+    match board.state() {
+        State::Turn(_) => Some(MiniMaxNode::new(board).calculate_parallel()),
+        _ => None,
+    }
+}
+
+/// Deepens the search one move at a time until `budget` is used up and returns the
+/// best move from the deepest search that finished. Always answers for a game in
+/// progress, even with a zero budget.
+#[cfg(feature = "std")]
+pub fn best_move_timed(board: &Board, budget: Duration) -> Option<u8> {
+    match board.state() {
+        State::Turn(_) => Some(MiniMaxNode::new(board).calculate_timed(budget)),
+        _ => None,
+    }
+}
+
+/// Deepens the search one move at a time until `cancel` is set, typically from
+/// another thread, and returns the best move from the deepest search that finished.
+/// Always answers for a game in progress, even if `cancel` is already set.
+#[cfg(feature = "std")]
+pub fn best_move_cancellable(board: &Board, cancel: &AtomicBool) -> Option<u8> {
+    match board.state() {
+        State::Turn(_) => Some(MiniMaxNode::new(board).calculate_cancellable(cancel)),
+        _ => None,
+    }
+}
+
+/// Like `best_move`, but picks among equally good moves using `seed`.
+#[cfg(feature = "std")]
+pub fn best_move_seeded(board: &Board, seed: u64) -> Option<u8> {
+    best_move_with_rng(board, &mut Rng::new(seed))
+}
+
+/// Like `best_move`, but with probability `blunder_prob` plays a uniformly random
+/// legal move instead. With `0.0` this always agrees with `best_move`.
+#[cfg(feature = "std")]
+pub fn best_move_with_blunder(board: &Board, blunder_prob: f64, rng: &mut Rng) -> Option<u8> {
+    if rng.next_f64() < blunder_prob {
+        let moves: Vec<usize> = board.legal_moves().collect();
+        if moves.is_empty() {
+            return None;
+        }
+        return Some(moves[rng.below(moves.len())] as u8);
+    }
+    best_move(board)
+}
+
+/// Every legal move as good as `best_move`, in board order. Empty if the game is
+/// already over.
+#[cfg(feature = "std")]
+pub fn best_moves(board: &Board) -> Vec<u8> {
+    OpeningBook::standard()
+        .best_moves(board)
+        .or_else(|| MiniMaxNode::new(board).best_moves(Limits::default()).0)
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn best_move_with_rng(board: &Board, rng: &mut Rng) -> Option<u8> {
+    let moves = best_moves(board);
+    if moves.is_empty() {
+        return None;
+    }
+    Some(moves[rng.below(moves.len())])
+}
+
+/// The moves perfect play by both sides makes from this position to the end of the
+/// game, starting with `best_move`. Empty if the game is already over.
+#[cfg(feature = "std")]
+pub fn best_line(board: &Board) -> Vec<u8> {
+    let mut board = board.clone();
+    let mut line = Vec::new();
+    while let Some(mve) = best_move(&board) {
+        board.place(mve as usize).unwrap();
+        line.push(mve);
+    }
+    line
+}
+
+/// `best_move` run on Tokio's blocking thread pool, so awaiting it doesn't stall the
+/// runtime's other tasks. Must be called from within a Tokio runtime.
+#[cfg(feature = "tokio")]
+pub async fn best_move_async(board: Board) -> Option<u8> {
+    tokio::task::spawn_blocking(move || best_move(&board))
+        .await
+        .expect("the search panicked")
+}
+
+/// Lets the engine play both sides until the game ends, calling `observe` after every move.
+#[cfg(feature = "std")]
+pub fn self_play(mut board: Board, mut observe: impl FnMut(&Board)) -> Board {
+    while let Some(mve) = best_move(&board) {
+        board.place(mve as usize).unwrap();
+        observe(&board);
+    }
+    board
+}
@@ -0,0 +1,624 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{debug, trace};
+
+use crate::board::{Board, Mark, State};
+use crate::evaluator::{Evaluator, TwoInARowEvaluator};
+
+#[derive(Clone)]
+enum NodeType {
+    Unfinished(Board),
+    Value(i32),
+}
+
+// The grid rather than the board's fields, so a failed search shows the position.
+impl fmt::Debug for NodeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeType::Unfinished(board) => write!(f, "{:?}:\n{}", board.state(), board),
+            NodeType::Value(value) => write!(f, "Value({})", value),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MiniMaxNode {
+    kind: NodeType,
+    moves: Vec<u8>,
+}
+
+/// Shows the moves that led to the node and then the board it holds as a grid,
+/// or its value once searched.
+impl fmt::Debug for MiniMaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MiniMaxNode after {:?}, {:?}", self.moves, self.kind)
+    }
+}
+
+impl MiniMaxNode {
+    pub fn new(board: &Board) -> Self {
+        Self {
+            kind: NodeType::Unfinished(board.clone()),
+            moves: Vec::new(),
+        }
+    }
+
+    /// Among equally good moves this prefers the center, then the corners, then the
+    /// edges, and within a group the lowest index; see `tie_break`.
+    pub fn calculate(self) -> u8 {
+        self.pick(Limits::default()).0.unwrap()
+    }
+
+    /// Like `calculate`, but also reports how much work the search did.
+    pub fn calculate_with_stats(self) -> (u8, SearchStats) {
+        let (mve, stats) = self.pick(Limits::default());
+        (mve.unwrap(), stats)
+    }
+
+    /// Like `calculate_with_memo`, but with the memo behind a lock so other threads
+    /// can search with it at the same time.
+    pub(crate) fn calculate_shared(self, memory: &RwLock<Memo>) -> (u8, SearchStats) {
+        let (mve, stats) = self.pick_in(Limits::default(), memory);
+        (mve.unwrap(), stats)
+    }
+
+    /// Like `calculate_with_stats`, but reuses the positions already solved in
+    /// `memo` and adds the ones this search solves.
+    pub fn calculate_with_memo(self, memo: &mut Memo) -> (u8, SearchStats) {
+        let memory = RwLock::new(std::mem::take(memo));
+        let (mve, stats) = self.pick_in(Limits::default(), &memory);
+        *memo = memory.into_inner().unwrap();
+        (mve.unwrap(), stats)
+    }
+
+    /// Only looks `max_depth` moves ahead, scoring unfinished positions at the
+    /// cutoff with `Board::evaluate`.
+    pub fn calculate_with_depth(self, max_depth: usize) -> u8 {
+        self.pick(Limits {
+            max_depth: Some(max_depth),
+            ..Limits::default()
+        })
+        .0
+        .unwrap()
+    }
+
+    /// Like `calculate_with_depth`, but scores the positions at the cutoff with
+    /// `evaluator`.
+    pub fn calculate_with_evaluator(self, max_depth: usize, evaluator: &dyn Evaluator) -> u8 {
+        self.pick(Limits {
+            max_depth: Some(max_depth),
+            evaluator: Some(evaluator),
+            ..Limits::default()
+        })
+        .0
+        .unwrap()
+    }
+
+    /// Like `calculate`, but searches each possible move on its own thread.
+    pub fn calculate_parallel(self) -> u8 {
+        self.pick(Limits {
+            parallel: true,
+            ..Limits::default()
+        })
+        .0
+        .unwrap()
+    }
+
+    /// Searches one move deeper at a time until `budget` runs out and returns the
+    /// answer of the deepest search that finished. The one-move search always runs
+    /// to completion, so there is an answer even with no budget at all.
+    pub fn calculate_timed(self, budget: Duration) -> u8 {
+        self.deepen(Limits {
+            deadline: Some(Instant::now() + budget),
+            ..Limits::default()
+        })
+    }
+
+    /// Like `calculate_timed`, but runs until `cancel` is set from another thread
+    /// instead of until a deadline. If it never is, this is the full search.
+    pub fn calculate_cancellable(self, cancel: &AtomicBool) -> u8 {
+        self.deepen(Limits {
+            cancel: Some(cancel),
+            ..Limits::default()
+        })
+    }
+
+    // Iterative deepening until `limits` stops a search short.
+    fn deepen(self, limits: Limits) -> u8 {
+        let empty = match &self.kind {
+            NodeType::Unfinished(board) => board.legal_moves().count(),
+            NodeType::Value(_) => 0,
+        };
+        let mut best = self.clone().calculate_with_depth(1);
+        for depth in 2..=empty {
+            let limits = Limits {
+                max_depth: Some(depth),
+                ..limits
+            };
+            match self.clone().pick(limits).0 {
+                Some(mve) => best = mve,
+                None => break,
+            }
+        }
+        best
+    }
+
+    // `None` if the deadline passed or the search was cancelled before it finished.
+    fn pick(self, limits: Limits) -> (Option<u8>, SearchStats) {
+        self.pick_in(limits, &RwLock::new(Memo::default()))
+    }
+
+    fn pick_in(self, limits: Limits, memory: &RwLock<Memo>) -> (Option<u8>, SearchStats) {
+        let priority = match &self.kind {
+            NodeType::Unfinished(board) => line_counts(board),
+            NodeType::Value(_) => Vec::new(),
+        };
+        let node = self.clone();
+        let (moves, stats) = self.best_moves_in(limits, memory);
+        let best = moves.map(|moves| {
+            moves
+                .into_iter()
+                .min_by(|&a, &b| tie_break(&priority, a, b))
+                .unwrap_or_else(|| panic!("no moves left to calculate at {:?}", node))
+        });
+        (best, stats)
+    }
+
+    // Runs one search from this node and returns the move it settled on, `None` if
+    // the game is over, with how many nodes it evaluated. `prune: false` disables
+    // alpha-beta cutoffs and walks the whole tree. Only the tests need this, to
+    // measure pruning, so it stays off the documented API.
+    #[doc(hidden)]
+    pub fn count_nodes(self, prune: bool) -> (Option<u8>, u64) {
+        let depth = self.moves.len();
+        let (res, nodes) = self.search(prune, true);
+        (res.moves.get(depth).copied(), nodes)
+    }
+
+    // Like `count_nodes` with pruning, but tries moves in board order instead of
+    // center and corners first.
+    #[doc(hidden)]
+    pub fn count_nodes_unordered(self) -> (Option<u8>, u64) {
+        let depth = self.moves.len();
+        let (res, nodes) = self.search(true, false);
+        (res.moves.get(depth).copied(), nodes)
+    }
+
+    /// The score of this position under perfect play by both sides, from X's point
+    /// of view: positive if X wins, negative if O wins and zero for a draw.
+    pub fn calculate_value(self) -> i32 {
+        self.search(true, true).0.value()
+    }
+
+    /// Every move that is as good as the best one, in board order, or `None` if the
+    /// deadline passed before the search finished.
+    pub(crate) fn best_moves(self, limits: Limits) -> (Option<Vec<u8>>, SearchStats) {
+        self.best_moves_in(limits, &RwLock::new(Memo::default()))
+    }
+
+    pub(crate) fn best_moves_in(
+        self,
+        limits: Limits,
+        memory: &RwLock<Memo>,
+    ) -> (Option<Vec<u8>>, SearchStats) {
+        let depth = self.moves.len();
+        let board = match self.kind {
+            NodeType::Unfinished(board) => board,
+            NodeType::Value(_) => return (Some(Vec::new()), SearchStats::default()),
+        };
+        let m = match board.get_new_state() {
+            State::Turn(m) => m,
+            _ => return (Some(Vec::new()), SearchStats::default()),
+        };
+        let max_depth = limits.max_depth.map(|d| d.max(1) + depth);
+        let priority = line_counts(&board);
+        // Each root move gets the full window, so equal values are exact rather than bounds.
+        let evaluate = |mve: usize| {
+            let mut search = Search {
+                memory,
+                stats: SearchStats::default(),
+                root_depth: depth,
+                priority: Some(&priority),
+                prune: true,
+                max_depth,
+                evaluator: limits.evaluator.unwrap_or(&TwoInARowEvaluator),
+                symmetric: limits.evaluator.is_none(),
+                deadline: limits.deadline,
+                cancel: limits.cancel,
+                aborted: false,
+            };
+            let mut new_board = board.clone();
+            new_board.place(mve).unwrap();
+            let value = search
+                .minimax(&mut new_board, depth + 1, -i32::MAX, i32::MAX)
+                .value;
+            if search.aborted {
+                trace!("move {} ran out of time", mve);
+            } else {
+                debug!(
+                    "move {} scores {} ({} nodes, {} memo hits)",
+                    mve, value, search.stats.nodes_visited, search.stats.memo_hits
+                );
+            }
+            (
+                (!search.aborted).then_some((mve as u8, value)),
+                search.stats,
+            )
+        };
+        let results: Vec<(Option<(u8, i32)>, SearchStats)> = if limits.parallel {
+            thread::scope(|s| {
+                let handles: Vec<_> = board
+                    .legal_moves()
+                    .map(|mve| s.spawn(move || evaluate(mve)))
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            })
+        } else {
+            board.legal_moves().map(evaluate).collect()
+        };
+        let mut stats = SearchStats::default();
+        for (_, s) in &results {
+            stats.add(s);
+        }
+        let scored: Option<Vec<(u8, i32)>> = results.into_iter().map(|(r, _)| r).collect();
+        let moves = scored.map(|scored| {
+            let best = scored.iter().map(|&(_, v)| v * m.to_value() as i32).max();
+            scored
+                .into_iter()
+                .filter(|&(_, v)| Some(v * m.to_value() as i32) == best)
+                .map(|(mve, _)| mve)
+                .collect()
+        });
+        debug!(
+            "best for {}: {:?} after {} nodes, {} memo hits, {} moves deep",
+            m, moves, stats.nodes_visited, stats.memo_hits, stats.max_depth_reached
+        );
+        (moves, stats)
+    }
+
+    fn search(self, prune: bool, ordered: bool) -> (MiniMaxNode, u64) {
+        let depth = self.moves.len();
+        let memory = RwLock::new(Memo::default());
+        let priority = match &self.kind {
+            NodeType::Unfinished(board) if ordered => line_counts(board),
+            _ => Vec::new(),
+        };
+        let mut search = Search {
+            memory: &memory,
+            stats: SearchStats::default(),
+            root_depth: depth,
+            priority: ordered.then_some(&priority[..]),
+            prune,
+            max_depth: None,
+            evaluator: &TwoInARowEvaluator,
+            symmetric: true,
+            deadline: None,
+            cancel: None,
+            aborted: false,
+        };
+        let mut board = match self.kind {
+            NodeType::Unfinished(board) => board,
+            NodeType::Value(_) => return (self, 1),
+        };
+        let scored = search.minimax(&mut board, depth, -i32::MAX, i32::MAX);
+        let mut moves = self.moves;
+        moves.extend(scored.best_move);
+        let res = MiniMaxNode {
+            kind: NodeType::Value(scored.value),
+            moves,
+        };
+        (res, search.stats.nodes_visited)
+    }
+
+    fn value(&self) -> i32 {
+        match self.kind {
+            NodeType::Unfinished(_) => {
+                panic!(
+                    "either memory or the rules of tic tac toe are broken at {:?}",
+                    self
+                )
+            }
+            NodeType::Value(i) => i,
+        }
+    }
+}
+
+// How many lines pass through each cell. On 3x3 that is four for the center,
+// three for the corners and two for the edges.
+pub(crate) fn line_counts(board: &Board) -> Vec<usize> {
+    let mut counts = vec![0; board.marks.len()];
+    for line in board.lines() {
+        for i in board.line_cells(line) {
+            counts[i] += 1;
+        }
+    }
+    counts
+}
+
+// Cells on more lines sort first, then lower indices.
+pub(crate) fn tie_break(line_counts: &[usize], a: u8, b: u8) -> Ordering {
+    line_counts[b as usize]
+        .cmp(&line_counts[a as usize])
+        .then(a.cmp(&b))
+}
+
+// Scores are from X's point of view. Wins are worth more the fewer marks are on
+// the board, so the winner prefers quick wins and the loser prefers slow losses.
+// `win_value` is larger than any count of empty cells, so this orders scores like
+// the pair (outcome, -distance to the end): the outcome always decides first.
+fn win_score(winner: Mark, board: &Board) -> i32 {
+    let empty = board.empty_count() as i32;
+    winner.to_value() as i32 * (board.win_value() + empty)
+}
+
+// With pruning a node's value may only be a bound on its true value, which is
+// still enough to reuse the entry whenever it would cause the same cutoff.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+// What the memo knows about a position: its value, how far that value can be
+// trusted, the move that gets it in the canonical orientation, and how many moves
+// the game lasts from here that way.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Entry {
+    value: i32,
+    bound: Bound,
+    best_move: u8,
+    dist: u8,
+}
+
+// The memo holds a lot of these, so keep them small.
+const _: () = assert!(std::mem::size_of::<Entry>() <= 8);
+
+// What searching one position found: its value, the move that gets it (`None` where
+// the search stopped), and how many moves that takes to the end or the cutoff.
+#[derive(Clone, Copy, Debug)]
+struct Scored {
+    value: i32,
+    best_move: Option<u8>,
+    dist: u8,
+}
+
+impl Scored {
+    fn leaf(value: i32) -> Self {
+        Scored {
+            value,
+            best_move: None,
+            dist: 0,
+        }
+    }
+}
+
+/// Positions the engine has already solved, keyed on their canonical orientation.
+/// Pass one to `calculate_with_memo` to carry work over from one search to the next.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Vec<(Board, Entry)>", into = "Vec<(Board, Entry)>")
+)]
+pub struct Memo {
+    entries: HashMap<Board, Entry>,
+}
+
+impl Memo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many positions are stored.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The best move stored for `board`, if a search solved it exactly.
+    pub fn best_move(&self, board: &Board) -> Option<u8> {
+        let (key, perm) = board.canonical_with_symmetry();
+        let entry = self.entries.get(&key)?;
+        if !matches!(entry.bound, Bound::Exact) {
+            return None;
+        }
+        perm.iter()
+            .position(|&p| p == entry.best_move as usize)
+            .map(|i| i as u8)
+    }
+}
+
+// Boards can't be JSON object keys, so the table goes to disk as a list of pairs.
+#[cfg(feature = "serde")]
+impl From<Vec<(Board, Entry)>> for Memo {
+    fn from(entries: Vec<(Board, Entry)>) -> Self {
+        Self {
+            entries: entries.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Memo> for Vec<(Board, Entry)> {
+    fn from(memo: Memo) -> Self {
+        memo.entries.into_iter().collect()
+    }
+}
+
+// Knobs for a search from the root; the default is the exhaustive single-threaded search.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Limits<'a> {
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) parallel: bool,
+    pub(crate) deadline: Option<Instant>,
+    // Stops the search as soon as it's set.
+    pub(crate) cancel: Option<&'a AtomicBool>,
+    // Scores the cutoff positions; `TwoInARowEvaluator` if unset.
+    pub(crate) evaluator: Option<&'a dyn Evaluator>,
+}
+
+/// Counters collected while searching.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Positions the search expanded or scored, not counting memo hits.
+    pub nodes_visited: u64,
+    /// Positions answered straight from the memo.
+    pub memo_hits: u64,
+    /// The most moves below the root the search looked.
+    pub max_depth_reached: usize,
+}
+
+impl SearchStats {
+    fn add(&mut self, other: &SearchStats) {
+        self.nodes_visited += other.nodes_visited;
+        self.memo_hits += other.memo_hits;
+        self.max_depth_reached = self.max_depth_reached.max(other.max_depth_reached);
+    }
+}
+
+// The memo is shared behind a lock so root moves can be searched on separate
+// threads, and one `Engine` can serve several at once. Lookups only need to read.
+struct Search<'a> {
+    memory: &'a RwLock<Memo>,
+    stats: SearchStats,
+    root_depth: usize,
+    // Line counts per cell, if moves should be tried best-looking first.
+    priority: Option<&'a [usize]>,
+    prune: bool,
+    max_depth: Option<usize>,
+    evaluator: &'a dyn Evaluator,
+    // Whether mirror images score the same, so they can share a memo entry. Only
+    // the built-in evaluator is known to be; a custom one may tell them apart.
+    symmetric: bool,
+    deadline: Option<Instant>,
+    cancel: Option<&'a AtomicBool>,
+    // Set once the deadline passes or the search is cancelled; every value computed
+    // after that is meaningless.
+    aborted: bool,
+}
+
+impl Search<'_> {
+    // The memo key for `board` and the permutation of its cells into the key.
+    fn key(&self, board: &Board) -> (Board, Vec<usize>) {
+        match self.symmetric {
+            true => board.canonical_with_symmetry(),
+            false => {
+                let identity: Vec<usize> = (0..board.marks.len()).collect();
+                (board.transformed(&identity), identity)
+            }
+        }
+    }
+
+    // Scores `board`, which is `ply` moves below the node the search started from,
+    // and returns the value with the move that leads to it. Moves are made
+    // on `board` and taken back again, so it is unchanged on return.
+    fn minimax(&mut self, board: &mut Board, ply: usize, mut alpha: i32, mut beta: i32) -> Scored {
+        self.stats.nodes_visited += 1;
+        let depth = ply - self.root_depth;
+        self.stats.max_depth_reached = self.stats.max_depth_reached.max(depth);
+        if self.deadline.is_some_and(|d| Instant::now() >= d)
+            || self.cancel.is_some_and(|c| c.load(AtomicOrdering::Relaxed))
+        {
+            self.aborted = true;
+        }
+        if self.aborted {
+            return Scored::leaf(0);
+        }
+        let m = match board.get_new_state() {
+            State::Won(m) => return Scored::leaf(win_score(m, board)),
+            State::Tie => return Scored::leaf(0),
+            State::Turn(m) => m,
+        };
+        // Nobody can win from here, so don't bother playing it out.
+        if board.is_forced_draw() {
+            return Scored::leaf(0);
+        }
+        // A win on the next move is worth one more ply, since the heuristic can't
+        // see it coming; past that the cutoff is final.
+        if let Some(d) = self.max_depth {
+            if ply > d || (ply == d && board.threats(m).is_empty()) {
+                return Scored::leaf(self.evaluator.score(board));
+            }
+        }
+        let mut possible: Vec<u8> = board.legal_moves().map(|i| i as u8).collect();
+        // Strong moves first make for earlier cutoffs.
+        if let Some(priority) = self.priority {
+            possible.sort_by(|&a, &b| tie_break(priority, a, b));
+        }
+        let (alpha_orig, beta_orig) = (alpha, beta);
+        let mut best: Option<Scored> = None;
+        for mve in possible {
+            board.place(mve as usize).unwrap();
+            let (key, _) = self.key(board);
+            let cached = self
+                .memory
+                .read()
+                .unwrap()
+                .entries
+                .get(&key)
+                .filter(|entry| match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => entry.value >= beta,
+                    Bound::Upper => entry.value <= alpha,
+                })
+                .map(|entry| (entry.value, entry.dist));
+            let (value, dist) = match cached {
+                Some(result) => {
+                    self.stats.memo_hits += 1;
+                    result
+                }
+                None => {
+                    let child = self.minimax(board, ply + 1, alpha, beta);
+                    (child.value, child.dist)
+                }
+            };
+            board.undo();
+            if best.is_none_or(|b| value * m.to_value() as i32 > b.value * m.to_value() as i32) {
+                best = Some(Scored {
+                    value,
+                    best_move: Some(mve),
+                    dist: dist + 1,
+                });
+            }
+            match m {
+                Mark::X => alpha = alpha.max(value),
+                Mark::O => beta = beta.min(value),
+            }
+            if self.aborted || (self.prune && alpha >= beta) {
+                break;
+            }
+        }
+        let best = best.unwrap();
+        let bound = if !self.prune || (alpha_orig < best.value && best.value < beta_orig) {
+            Bound::Exact
+        } else if best.value <= alpha_orig {
+            Bound::Upper
+        } else {
+            Bound::Lower
+        };
+        if self.aborted {
+            return best;
+        }
+        let (key, perm) = self.key(board);
+        let entry = Entry {
+            value: best.value,
+            bound,
+            best_move: perm[best.best_move.unwrap() as usize] as u8,
+            dist: best.dist,
+        };
+        self.memory.write().unwrap().entries.insert(key, entry);
+        best
+    }
+}
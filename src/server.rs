@@ -0,0 +1,253 @@
+//! A tiny JSON-over-HTTP front end. Every request carries the whole board, so the
+//! server keeps no state between requests.
+//!
+//! - `GET /new` answers `{"board": ..., "ai_move": null}` with an empty board.
+//! - `POST /move` takes `{"board": ..., "move": 4}`, plays the move and the engine's
+//!   reply, and answers `{"board": ..., "ai_move": 0}`. `ai_move` is `null` when the
+//!   player's move ended the game.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::best_move;
+use crate::board::Board;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Deserialize)]
+struct MoveRequest {
+    board: Board,
+    #[serde(rename = "move")]
+    mve: usize,
+}
+
+#[derive(Serialize)]
+struct MoveResponse {
+    board: Board,
+    ai_move: Option<u8>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// The longest request or header line `Request::read` takes, line ending included.
+pub const MAX_LINE_LEN: usize = 1024;
+
+/// The most header lines `Request::read` takes.
+pub const MAX_HEADERS: usize = 64;
+
+/// The largest body `Request::read` takes. A board in JSON is well under this.
+pub const MAX_BODY_LEN: usize = 4096;
+
+/// How long `serve` waits on a client to send its request or take the response
+/// before hanging up on it, so one that stalls can't hold up the rest.
+pub const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Why `Request::read` gave up on a request.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The connection failed or timed out.
+    Io(io::Error),
+    /// The request line or a header isn't valid HTTP.
+    Malformed(&'static str),
+    /// A line, the headers or the body is over its limit. Nothing that large is
+    /// read into memory.
+    TooLarge(&'static str),
+}
+
+impl ReadError {
+    /// The status to answer the request with.
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::Io(_) | Self::Malformed(_) => 400,
+            Self::TooLarge(_) => 413,
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Malformed(what) => write!(f, "bad {}", what),
+            Self::TooLarge(what) => write!(f, "{} too large", what),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+// Reads a line of at most `MAX_LINE_LEN` bytes, stopping there on longer ones.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> Result<usize, ReadError> {
+    line.clear();
+    let n = reader
+        .take(MAX_LINE_LEN as u64 + 1)
+        .read_line(line)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData => ReadError::Malformed("header encoding"),
+            _ => ReadError::Io(e),
+        })?;
+    if n > MAX_LINE_LEN {
+        return Err(ReadError::TooLarge("header line"));
+    }
+    Ok(n)
+}
+
+impl Request {
+    /// Reads one HTTP/1.1 request: the request line, the headers and a body of
+    /// `Content-Length` bytes, each held to the limits above.
+    pub fn read(reader: &mut impl BufRead) -> Result<Request, ReadError> {
+        let mut line = String::new();
+        read_line(reader, &mut line)?;
+        let mut parts = line.split_whitespace();
+        let (method, path) = match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+            _ => return Err(ReadError::Malformed("request line")),
+        };
+        let mut length = 0;
+        let mut headers = 0;
+        loop {
+            if read_line(reader, &mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            headers += 1;
+            if headers > MAX_HEADERS {
+                return Err(ReadError::TooLarge("headers"));
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    length = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| ReadError::Malformed("Content-Length"))?;
+                }
+            }
+        }
+        if length > MAX_BODY_LEN {
+            return Err(ReadError::TooLarge("body"));
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        let body = String::from_utf8(body).map_err(|_| ReadError::Malformed("body encoding"))?;
+        Ok(Request { method, path, body })
+    }
+}
+
+impl Response {
+    fn json(status: u16, body: &impl Serialize) -> Response {
+        Response {
+            status,
+            body: serde_json::to_string(body).unwrap(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Response {
+        Response::json(
+            status,
+            &ErrorResponse {
+                error: message.into(),
+            },
+        )
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            413 => "Payload Too Large",
+            _ => "Error",
+        };
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            self.body.len(),
+            self.body
+        )
+    }
+}
+
+/// Answers one request; see the module docs for the endpoints.
+pub fn handle(request: &Request) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/new") => Response::json(
+            200,
+            &MoveResponse {
+                board: Board::new(),
+                ai_move: None,
+            },
+        ),
+        ("POST", "/move") => play(&request.body),
+        _ => Response::error(
+            404,
+            format!("no endpoint {} {}", request.method, request.path),
+        ),
+    }
+}
+
+fn play(body: &str) -> Response {
+    let MoveRequest { mut board, mve } = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => return Response::error(400, e.to_string()),
+    };
+    // Searching anything bigger would tie up the server for minutes.
+    if board.size() != 3 || board.win_len() != 3 || board.marks().len() != 9 {
+        return Response::error(400, "only 3x3 boards are supported");
+    }
+    if let Err(e) = board.place(mve) {
+        return Response::error(400, e.to_string());
+    }
+    let ai_move = best_move(&board);
+    if let Some(reply) = ai_move {
+        board.place(reply as usize).unwrap();
+    }
+    Response::json(200, &MoveResponse { board, ai_move })
+}
+
+/// Serves requests on `addr` one connection at a time until the listener fails.
+/// Each connection gets `IO_TIMEOUT` to send its request and read the answer.
+pub fn serve(addr: impl ToSocketAddrs) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let timeouts = stream
+            .set_read_timeout(Some(IO_TIMEOUT))
+            .and(stream.set_write_timeout(Some(IO_TIMEOUT)));
+        // Without them the client could hang the server, so drop it instead.
+        if timeouts.is_err() {
+            continue;
+        }
+        let response = match Request::read(&mut BufReader::new(&stream)) {
+            Ok(request) => handle(&request),
+            Err(e) => Response::error(e.status(), e.to_string()),
+        };
+        // A client that hangs up early shouldn't take the server down with it.
+        let _ = response.write(&mut stream);
+    }
+    Ok(())
+}
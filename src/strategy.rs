@@ -0,0 +1,115 @@
+use crate::board::Board;
+use crate::rng::Rng;
+
+/// Something that can pick a move. `choose` is only called while the game is in progress.
+pub trait Strategy {
+    fn choose(&mut self, board: &Board) -> usize;
+
+    /// Whether to agree to a draw offered on `board`. By default that's only when
+    /// perfect play would draw anyway.
+    fn accepts_draw(&mut self, board: &Board) -> bool {
+        crate::position_value(board) == 0
+    }
+}
+
+/// Plays perfectly using the minimax search. Without a seed it always picks the
+/// same move among equally good ones; with one it picks among them at random.
+#[derive(Clone, Debug, Default)]
+pub struct MiniMaxStrategy {
+    rng: Option<Rng>,
+}
+
+impl MiniMaxStrategy {
+    pub fn new() -> Self {
+        MiniMaxStrategy { rng: None }
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        MiniMaxStrategy {
+            rng: Some(Rng::new(seed)),
+        }
+    }
+}
+
+impl Strategy for MiniMaxStrategy {
+    fn choose(&mut self, board: &Board) -> usize {
+        let mve = match &mut self.rng {
+            Some(rng) => crate::best_move_with_rng(board, rng),
+            None => crate::best_move(board),
+        };
+        mve.expect("choose called on a finished game") as usize
+    }
+}
+
+/// Picks uniformly among the legal moves.
+#[derive(Clone, Debug)]
+pub struct RandomStrategy {
+    rng: Rng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> Self {
+        RandomStrategy {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn choose(&mut self, board: &Board) -> usize {
+        let moves: Vec<usize> = board.legal_moves().collect();
+        assert!(!moves.is_empty(), "choose called on a finished game");
+        moves[self.rng.below(moves.len())]
+    }
+}
+
+/// Plays `opening` for its first `moves` moves of each game and `rest` after that.
+/// Moves are counted from the board, so one can play several games in a row.
+#[derive(Clone, Debug)]
+pub struct SwitchingStrategy<A, B> {
+    opening: A,
+    rest: B,
+    moves: usize,
+}
+
+impl<A: Strategy, B: Strategy> SwitchingStrategy<A, B> {
+    pub fn new(opening: A, rest: B, moves: usize) -> Self {
+        SwitchingStrategy {
+            opening,
+            rest,
+            moves,
+        }
+    }
+
+    // Whichever of the two moves next on `board`.
+    fn active(&mut self, board: &Board) -> &mut dyn Strategy {
+        let me = board.current_player();
+        let played = board
+            .marks()
+            .iter()
+            .filter(|&&m| m.is_some() && m == me)
+            .count();
+        match played < self.moves {
+            true => &mut self.opening,
+            false => &mut self.rest,
+        }
+    }
+}
+
+impl SwitchingStrategy<RandomStrategy, MiniMaxStrategy> {
+    /// A random first move, then perfect play from wherever that leaves it. Moving
+    /// first that can't lose, since every opening draws; moving second it can.
+    pub fn random_opening(seed: u64) -> Self {
+        SwitchingStrategy::new(RandomStrategy::new(seed), MiniMaxStrategy::new(), 1)
+    }
+}
+
+impl<A: Strategy, B: Strategy> Strategy for SwitchingStrategy<A, B> {
+    fn choose(&mut self, board: &Board) -> usize {
+        self.active(board).choose(board)
+    }
+
+    fn accepts_draw(&mut self, board: &Board) -> bool {
+        self.active(board).accepts_draw(board)
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::board::{Board, Mark, State};
+
+/// Who wins a position if both sides play perfectly from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    XWins,
+    OWins,
+    Draw,
+}
+
+impl Outcome {
+    pub fn winner(self) -> Option<Mark> {
+        match self {
+            Outcome::XWins => Some(Mark::X),
+            Outcome::OWins => Some(Mark::O),
+            Outcome::Draw => None,
+        }
+    }
+
+    // Ordered the way X ranks outcomes, so O wants the smallest.
+    fn value(self) -> i8 {
+        match self {
+            Outcome::XWins => 1,
+            Outcome::Draw => 0,
+            Outcome::OWins => -1,
+        }
+    }
+}
+
+/// The perfect-play outcome of every position on the standard 3x3 board, whichever
+/// side went first, keyed by the `encode`d canonical board.
+#[derive(Clone, Debug, Default)]
+pub struct Tablebase {
+    results: HashMap<u32, Outcome>,
+}
+
+impl Tablebase {
+    /// Solves every position reachable from an empty board.
+    pub fn build() -> Self {
+        let mut results = HashMap::new();
+        for first in [Mark::X, Mark::O] {
+            solve(&mut Board::with_first_player(first), &mut results);
+        }
+        Tablebase { results }
+    }
+
+    /// The tablebase built on first use and shared from then on.
+    pub fn standard() -> &'static Tablebase {
+        static TABLEBASE: OnceLock<Tablebase> = OnceLock::new();
+        TABLEBASE.get_or_init(Tablebase::build)
+    }
+
+    /// How many positions the tablebase holds, one per symmetry class.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// The outcome of `board` under perfect play. Legal positions that no game
+    /// reaches, like a win with a move played after it, are solved on the spot.
+    /// Panics unless `board` is a 3x3 board under the standard rules.
+    pub fn result(&self, board: &Board) -> Outcome {
+        match self.results.get(&board.canonical().encode()) {
+            Some(&outcome) => outcome,
+            None => solve(&mut board.clone(), &mut HashMap::new()),
+        }
+    }
+}
+
+// Plays out every continuation of `board` once per symmetry class, recording each
+// position's outcome in `results`. `board` is unchanged on return.
+fn solve(board: &mut Board, results: &mut HashMap<u32, Outcome>) -> Outcome {
+    let key = board.canonical().encode();
+    if let Some(&outcome) = results.get(&key) {
+        return outcome;
+    }
+    let outcome = match *board.state() {
+        State::Won(Mark::X) => Outcome::XWins,
+        State::Won(Mark::O) => Outcome::OWins,
+        State::Tie => Outcome::Draw,
+        State::Turn(mark) => {
+            let moves: Vec<usize> = board.legal_moves().collect();
+            let children = moves.into_iter().map(|mve| {
+                board.place(mve).unwrap();
+                let outcome = solve(board, results);
+                board.undo();
+                outcome
+            });
+            let sign = mark.to_value();
+            children.max_by_key(|o| o.value() * sign).unwrap()
+        }
+    };
+    results.insert(key, outcome);
+    outcome
+}
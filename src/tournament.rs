@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::board::{Board, Mark, State};
+use crate::strategy::Strategy;
+
+/// One player's results against an opponent, or against the whole field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Record {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Record {
+    fn add(&mut self, other: &Record) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+
+    // The same games seen from the other side.
+    fn reversed(&self) -> Record {
+        Record {
+            wins: self.losses,
+            draws: self.draws,
+            losses: self.wins,
+        }
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.wins, self.draws, self.losses)
+    }
+}
+
+/// The outcome of `round_robin`: every player's record against every other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Standings {
+    // `records[i][j]` is player `i`'s record against player `j`.
+    records: Vec<Vec<Record>>,
+}
+
+impl Standings {
+    pub fn record(&self, player: usize, opponent: usize) -> Record {
+        self.records[player][opponent]
+    }
+
+    /// `player`'s record against everyone else combined.
+    pub fn total(&self, player: usize) -> Record {
+        let mut total = Record::default();
+        for record in &self.records[player] {
+            total.add(record);
+        }
+        total
+    }
+}
+
+/// One row per player with their wins-draws-losses against each column's player.
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n = self.records.len();
+        write!(f, "{:>4}", "")?;
+        for j in 0..n {
+            write!(f, " {:>9}", j)?;
+        }
+        writeln!(f)?;
+        for (i, row) in self.records.iter().enumerate() {
+            write!(f, "{:>4}", i)?;
+            for (j, record) in row.iter().enumerate() {
+                match i == j {
+                    true => write!(f, " {:>9}", "-")?,
+                    false => write!(f, " {:>9}", record.to_string())?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays every pair of `players` against each other `games` times with each of them
+/// moving first, on the standard board.
+pub fn round_robin(players: &mut [Box<dyn Strategy>], games: u32) -> Standings {
+    let n = players.len();
+    let mut records = vec![vec![Record::default(); n]; n];
+    for (i, j) in (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))) {
+        let (left, right) = players.split_at_mut(j);
+        let (a, b) = (left[i].as_mut(), right[0].as_mut());
+        let mut pair = Record::default();
+        for _ in 0..games {
+            pair.add(&result_for(play(a, b), Mark::X));
+            pair.add(&result_for(play(b, a), Mark::O));
+        }
+        records[i][j] = pair;
+        records[j][i] = pair.reversed();
+    }
+    Standings { records }
+}
+
+// A finished game as a record for the side that played `mark`.
+fn result_for(state: State, mark: Mark) -> Record {
+    let mut record = Record::default();
+    match state {
+        State::Won(m) if m == mark => record.wins += 1,
+        State::Won(_) => record.losses += 1,
+        _ => record.draws += 1,
+    }
+    record
+}
+
+// One game with `x` moving first, played to the end.
+fn play(x: &mut dyn Strategy, o: &mut dyn Strategy) -> State {
+    let mut board = Board::new();
+    while let Some(turn) = board.current_player() {
+        let mve = match turn {
+            Mark::X => x.choose(&board),
+            Mark::O => o.choose(&board),
+        };
+        board.place(mve).unwrap();
+    }
+    board.state().clone()
+}
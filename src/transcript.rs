@@ -0,0 +1,158 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::board::{Board, GameError, Mark};
+
+/// The moves of a game in order, written as `X4 O0 X8 ...`: the mark, then the cell.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transcript {
+    moves: Vec<(Mark, usize)>,
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The moves played on `board` so far. Boards built by parsing have no history,
+    /// so their transcript is empty.
+    pub fn from_board(board: &Board) -> Self {
+        let moves = board
+            .history()
+            .iter()
+            .map(|&i| {
+                (
+                    board.marks()[i].expect("history points at a filled cell"),
+                    i,
+                )
+            })
+            .collect();
+        Transcript { moves }
+    }
+
+    pub fn push(&mut self, mark: Mark, index: usize) {
+        self.moves.push((mark, index));
+    }
+
+    pub fn moves(&self) -> &[(Mark, usize)] {
+        &self.moves
+    }
+
+    /// Plays the moves onto a standard board where the first recorded mark starts.
+    pub fn replay(&self) -> Result<Board, GameError> {
+        let first = self.moves.first().map_or(Mark::X, |&(mark, _)| mark);
+        self.replay_on(Board::with_first_player(first))
+    }
+
+    /// Plays the moves onto `board`. A move by the side that isn't on turn fails
+    /// with `GameError::NotYourTurn`.
+    pub fn replay_on(&self, mut board: Board) -> Result<Board, GameError> {
+        for &(mark, index) in &self.moves {
+            play(&mut board, mark, index)?;
+        }
+        Ok(board)
+    }
+
+    /// Every board of the game on the standard board, from before the first move to
+    /// after the last, or the first move that can't be played.
+    pub fn positions(&self) -> Result<Vec<Board>, ReplayError> {
+        let first = self.moves.first().map_or(Mark::X, |&(mark, _)| mark);
+        let mut board = Board::with_first_player(first);
+        let mut boards = vec![board.clone()];
+        for (n, &(mark, index)) in self.moves.iter().enumerate() {
+            play(&mut board, mark, index).map_err(|error| ReplayError {
+                move_number: n + 1,
+                mark,
+                index,
+                error,
+            })?;
+            boards.push(board.clone());
+        }
+        Ok(boards)
+    }
+}
+
+fn play(board: &mut Board, mark: Mark, index: usize) -> Result<(), GameError> {
+    if board.current_player().is_some_and(|m| m != mark) {
+        return Err(GameError::NotYourTurn);
+    }
+    Ok(board.place(index)?)
+}
+
+/// A move in a transcript that couldn't be played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplayError {
+    /// Counting from 1.
+    pub move_number: usize,
+    pub mark: Mark,
+    pub index: usize,
+    pub error: GameError,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} ({}{}): {}",
+            self.move_number, self.mark, self.index, self.error
+        )
+    }
+}
+
+impl core::error::Error for ReplayError {}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, (mark, index)) in self.moves.iter().enumerate() {
+            if n > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}{}", mark, index)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseTranscriptError {
+    /// The move that didn't parse.
+    pub token: String,
+}
+
+impl fmt::Display for ParseTranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid move '{}', expected a mark and a cell like X4",
+            self.token
+        )
+    }
+}
+
+impl core::error::Error for ParseTranscriptError {}
+
+impl FromStr for Transcript {
+    type Err = ParseTranscriptError;
+
+    /// Reads the `Display` form. Moves are separated by whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |token: &str| {
+            let mut chars = token.chars();
+            let mark = chars.next().and_then(Mark::from_char)?;
+            let index = chars.as_str().parse().ok()?;
+            Some((mark, index))
+        };
+        let moves = s
+            .split_whitespace()
+            .map(|token| {
+                parse(token).ok_or_else(|| ParseTranscriptError {
+                    token: token.into(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Transcript { moves })
+    }
+}
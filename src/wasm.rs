@@ -0,0 +1,63 @@
+//! Browser bindings. Build with `cargo build-wasm`, run
+//! `wasm-bindgen --target web --out-dir pkg` on the resulting `tttai.wasm` and load
+//! the generated package from JavaScript.
+
+use wasm_bindgen::prelude::*;
+
+use crate::board::{Board, State};
+use crate::strategy::{MiniMaxStrategy, Strategy};
+
+/// A game for JavaScript to drive: it places marks for whoever's turn it is and
+/// lets the engine answer with `ai_move`.
+#[wasm_bindgen]
+pub struct WasmGame {
+    board: Board,
+    bot: MiniMaxStrategy,
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> WasmGame {
+        WasmGame {
+            board: Board::new(),
+            bot: MiniMaxStrategy::new(),
+        }
+    }
+
+    /// Plays `index` for the side to move. Returns `false` if that's not a legal move.
+    pub fn place(&mut self, index: u32) -> bool {
+        self.board.place(index as usize).is_ok()
+    }
+
+    /// Lets the engine move for the side to move and returns the cell it played,
+    /// or nothing if the game is over.
+    pub fn ai_move(&mut self) -> Option<u32> {
+        if self.board.is_over() {
+            return None;
+        }
+        let index = self.bot.choose(&self.board);
+        self.board.place(index).unwrap();
+        Some(index as u32)
+    }
+
+    /// `X` or `O`, or nothing for an empty or out-of-range cell.
+    pub fn cell(&self, index: u32) -> Option<char> {
+        self.board
+            .marks()
+            .get(index as usize)
+            .copied()
+            .flatten()
+            .map(|m| m.to_char())
+    }
+
+    /// A short description of the game: "X to move", "O won" or "Tie".
+    pub fn status(&self) -> String {
+        match self.board.state() {
+            State::Turn(m) => format!("{} to move", m.to_char()),
+            State::Won(m) => format!("{} won", m.to_char()),
+            State::Tie => "Tie".to_string(),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "tokio")]
+
+use tttai::{best_move, best_move_async, Board};
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn async_search_resolves_to_a_legal_move() {
+    let board = Board::from_moves(&[0, 4, 8]).unwrap();
+    let mve = block_on(best_move_async(board.clone())).unwrap();
+    assert!(board.legal_moves().any(|i| i == mve as usize));
+    assert_eq!(Some(mve), best_move(&board));
+}
+
+#[test]
+fn async_search_on_a_finished_board_is_none() {
+    let won = Board::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+    assert_eq!(block_on(best_move_async(won)), None);
+}
@@ -0,0 +1,870 @@
+use tttai::{
+    best_move, best_move_with_depth, Board, BoardError, BoardStyle, Mark, Outcomes,
+    ParseBoardError, PlaceError, RenderOptions, Rng, State, Theme, Variant, MAX_CELLS,
+};
+
+#[test]
+fn play_moves_through_public_api() {
+    let mut board = Board::new();
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+    for index in [0, 3, 1, 4] {
+        assert!(board.place(index).is_ok());
+    }
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+    assert!(board.place(4).is_err());
+    assert!(board.place(2).is_ok());
+    assert_eq!(board.state(), &State::Won(Mark::X));
+    assert!(board.place(5).is_err());
+    assert_eq!(best_move(&board), None);
+}
+
+#[test]
+fn default_is_a_new_game() {
+    assert_eq!(Board::default(), Board::new());
+    assert_eq!(format!("{:?}", Mark::X), "X");
+    assert_eq!(format!("{:?}", State::Tie), "Tie");
+}
+
+#[test]
+fn marks_parse_and_display() {
+    assert_eq!(Mark::from_char('x'), Some(Mark::X));
+    assert_eq!(Mark::from_char('O'), Some(Mark::O));
+    assert_eq!(Mark::from_char('?'), None);
+    assert_eq!(Mark::X.to_string(), "X");
+    assert_eq!(format!("{} then {}", Mark::O, Mark::X), "O then X");
+}
+
+#[test]
+fn marks_order_by_value() {
+    assert!(Mark::X > Mark::O);
+    assert_eq!(
+        Mark::X.cmp(&Mark::O),
+        Mark::X.to_value().cmp(&Mark::O.to_value())
+    );
+    assert_eq!([Mark::X, Mark::O].iter().min(), Some(&Mark::O));
+}
+
+#[test]
+fn current_player_follows_the_game() {
+    let mut board = Board::new();
+    assert_eq!(board.current_player(), Some(Mark::X));
+    board.place(4).unwrap();
+    assert_eq!(board.current_player(), Some(Mark::O));
+    let won: Board = "XXXOO....".parse().unwrap();
+    assert_eq!(won.current_player(), None);
+    let tie: Board = "XOXXOOOXX".parse().unwrap();
+    assert_eq!(tie.current_player(), None);
+}
+
+#[test]
+fn counts_empty_cells_and_moves() {
+    let mut board = Board::new();
+    assert_eq!((board.empty_count(), board.move_count()), (9, 0));
+    for index in [0, 4, 8] {
+        board.place(index).unwrap();
+    }
+    assert_eq!((board.empty_count(), board.move_count()), (6, 3));
+    let big = Board::with_size(4, 3);
+    assert_eq!((big.empty_count(), big.move_count()), (16, 0));
+}
+
+#[test]
+fn can_win_while_a_line_is_still_in_reach() {
+    // X can finish the top row straight away.
+    let winning = Board::from_moves(&[0, 3, 1, 4]).unwrap();
+    assert!(winning.can_win(Mark::X));
+    assert!(winning.can_win(Mark::O));
+    // X's best is a draw, but an open line still counts.
+    let drawn = Board::from_moves(&[4, 0]).unwrap();
+    assert!(drawn.can_win(Mark::X));
+    assert!(Board::new().can_win(Mark::X));
+}
+
+#[test]
+fn can_win_is_lost_once_no_line_is_in_reach() {
+    // Only the right column is free of O, and X has two moves for its three cells.
+    let out_of_reach = Board::from_moves(&[3, 0, 6, 7, 1, 4]).unwrap();
+    assert!(!out_of_reach.can_win(Mark::X));
+    assert!(out_of_reach.can_win(Mark::O));
+    assert!(!out_of_reach.is_forced_draw());
+    // In a forced draw neither side can.
+    let dead: Board = "XOX...OXO".parse().unwrap();
+    assert!(!dead.can_win(Mark::X));
+    assert!(!dead.can_win(Mark::O));
+    let won = Board::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+    assert!(won.can_win(Mark::X));
+    assert!(!won.can_win(Mark::O));
+    assert!(!"XOXXOOOXX".parse::<Board>().unwrap().can_win(Mark::X));
+}
+
+#[test]
+fn misere_wins_come_from_the_opponents_lines() {
+    let mut board = Board::from_moves(&[3, 0, 6, 7, 1, 4]).unwrap();
+    board.set_variant(Variant::Misere);
+    assert!(board.can_win(Mark::X));
+    assert!(!board.can_win(Mark::O));
+}
+
+#[test]
+fn forced_draws_are_spotted_early() {
+    // X has two moves left, so the empty middle row is out of reach.
+    let dead: Board = "XOX...OXO".parse().unwrap();
+    assert!(dead.is_forced_draw());
+    assert_eq!(dead.state(), &State::Turn(Mark::X));
+    assert!(!Board::new().is_forced_draw());
+    assert!(!"XO.......".parse::<Board>().unwrap().is_forced_draw());
+    assert!(!"XOXXOOOXX".parse::<Board>().unwrap().is_forced_draw());
+}
+
+#[test]
+fn open_lines_and_threats() {
+    // X threatens the top row; O's center blocks everything through it.
+    let board: Board = "XX..O....".parse().unwrap();
+    assert_eq!(board.threats(Mark::X), vec![vec![0, 1, 2]]);
+    assert!(board.threats(Mark::O).is_empty());
+    let open = board.open_lines();
+    assert_eq!(open.len(), 6);
+    assert!(open.contains(&(vec![0, 1, 2], Some(Mark::X))));
+    assert!(open.contains(&(vec![6, 7, 8], None)));
+    assert!(open.contains(&(vec![3, 4, 5], Some(Mark::O))));
+    assert!(!open.iter().any(|(cells, _)| cells == &vec![0, 4, 8]));
+    assert_eq!(Board::new().open_lines().len(), 8);
+}
+
+#[test]
+fn from_moves_replays_in_order() {
+    let board = Board::from_moves(&[4, 0, 8]).unwrap();
+    assert_eq!(board, "O...X...X".parse().unwrap());
+    assert_eq!(Board::from_moves(&[4, 4]), Err(PlaceError::Occupied));
+    assert_eq!(Board::from_moves(&[]), Ok(Board::new()));
+}
+
+#[test]
+fn o_can_move_first() {
+    let mut board = Board::with_first_player(Mark::O);
+    assert_eq!(board.state(), &State::Turn(Mark::O));
+    board.place(4).unwrap();
+    assert_eq!(board.marks()[4], Some(Mark::O));
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+}
+
+#[test]
+fn display_renders_grid() {
+    let mut board = Board::new();
+    for index in [4, 0, 8] {
+        board.place(index).unwrap();
+    }
+    assert_eq!(board.to_string(), "O| | \n |X| \n | |X\n");
+}
+
+#[test]
+fn parse_compact_and_rows() {
+    let board: Board = "X.O.X...O".parse().unwrap();
+    assert_eq!(board.marks()[0], Some(Mark::X));
+    assert_eq!(board.marks()[2], Some(Mark::O));
+    assert_eq!(board.marks()[1], None);
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+    assert_eq!("X.O\n.X.\n..O".parse::<Board>(), Ok(board));
+
+    let won: Board = "XXXOO....".parse().unwrap();
+    assert_eq!(won.state(), &State::Won(Mark::X));
+}
+
+#[test]
+fn parse_rejects_bad_input() {
+    assert_eq!("X.O".parse::<Board>(), Err(ParseBoardError::Length));
+    assert_eq!(
+        "X.O.Z....".parse::<Board>(),
+        Err(ParseBoardError::InvalidChar('Z'))
+    );
+    assert_eq!(
+        "XXX......".parse::<Board>(),
+        Err(ParseBoardError::MarkCount)
+    );
+}
+
+#[test]
+fn validate_accepts_reachable_boards() {
+    assert_eq!(Board::new().validate(), Ok(()));
+    assert_eq!(Board::from_moves(&[4, 0, 8]).unwrap().validate(), Ok(()));
+    assert_eq!(Board::with_first_player(Mark::O).validate(), Ok(()));
+    // One move can complete two lines at once.
+    let double: Board = "XOX\nOXO\nXOX".parse().unwrap();
+    assert_eq!(double.validate(), Ok(()));
+}
+
+#[test]
+fn parse_rejects_impossible_boards() {
+    assert_eq!(
+        "XXXXX....".parse::<Board>(),
+        Err(ParseBoardError::MarkCount)
+    );
+    assert_eq!(
+        "XXXOOO...".parse::<Board>(),
+        Err(ParseBoardError::Illegal(BoardError::BothWon))
+    );
+}
+
+#[test]
+fn display_round_trips() {
+    let mut board = Board::new();
+    for index in [4, 0, 8, 2] {
+        board.place(index).unwrap();
+    }
+    assert_eq!(board.to_string().parse::<Board>(), Ok(board));
+}
+
+#[test]
+fn undo_restores_earlier_position() {
+    let mut board = Board::new();
+    board.place(4).unwrap();
+    let after_first = board.clone();
+    board.place(0).unwrap();
+    board.place(8).unwrap();
+    assert_eq!(board.undo(), Some(8));
+    assert_eq!(board.undo(), Some(0));
+    assert_eq!(board, after_first);
+    assert_eq!(board.state(), &State::Turn(Mark::O));
+    assert_eq!(board.undo(), Some(4));
+    assert_eq!(board.undo(), None);
+    assert_eq!(board, Board::new());
+}
+
+#[test]
+fn undo_reopens_finished_game() {
+    let mut board = Board::new();
+    for index in [0, 3, 1, 4, 2] {
+        board.place(index).unwrap();
+    }
+    assert_eq!(board.state(), &State::Won(Mark::X));
+    assert_eq!(board.undo(), Some(2));
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+}
+
+#[test]
+fn winner_and_is_over() {
+    let won: Board = "XXXOO....".parse().unwrap();
+    assert_eq!(won.winner(), Some(Mark::X));
+    assert!(won.is_over());
+
+    let tied: Board = "XOXXOOOXX".parse().unwrap();
+    assert_eq!(tied.winner(), None);
+    assert!(tied.is_over());
+
+    let playing: Board = "X...O....".parse().unwrap();
+    assert_eq!(playing.winner(), None);
+    assert!(!playing.is_over());
+}
+
+#[test]
+fn winning_line_reports_cells() {
+    let top: Board = "XXXOO....".parse().unwrap();
+    assert_eq!(top.winning_line(), Some(vec![0, 1, 2]));
+
+    let diagonal: Board = "O.XOX.X..".parse().unwrap();
+    assert_eq!(diagonal.winning_line(), Some(vec![2, 4, 6]));
+
+    let playing: Board = "XX.OO....".parse().unwrap();
+    assert_eq!(playing.winning_line(), None);
+    assert_eq!(Board::new().winning_line(), None);
+}
+
+#[test]
+fn place_reports_why_it_failed() {
+    let mut board = Board::new();
+    assert_eq!(board.place(9), Err(PlaceError::OutOfRange));
+    board.place(4).unwrap();
+    assert_eq!(board.place(4), Err(PlaceError::Occupied));
+    let mut won: Board = "XXXOO....".parse().unwrap();
+    assert_eq!(won.place(8), Err(PlaceError::GameOver));
+}
+
+#[test]
+fn can_place_agrees_with_place_and_changes_nothing() {
+    let won: Board = "XXXOO....".parse().unwrap();
+    let mut gravity = Board::with_gravity(4, 5, 3);
+    gravity.place_column(1).unwrap();
+    let cases = [
+        (Board::from_moves(&[4]).unwrap(), 0, Ok(())),
+        (
+            Board::from_moves(&[4]).unwrap(),
+            9,
+            Err(PlaceError::OutOfRange),
+        ),
+        (
+            Board::from_moves(&[4]).unwrap(),
+            4,
+            Err(PlaceError::Occupied),
+        ),
+        (won, 8, Err(PlaceError::GameOver)),
+        (gravity.clone(), 2, Err(PlaceError::Unsupported)),
+        (gravity, 13, Ok(())),
+    ];
+    for (board, index, expected) in cases {
+        let before = board.clone();
+        assert_eq!(board.can_place(index), expected, "index {}", index);
+        assert_eq!(board, before);
+        assert_eq!(board.history(), before.history());
+        assert_eq!(board.state(), before.state());
+        assert_eq!(board.clone().place(index), expected);
+    }
+}
+
+#[test]
+fn place_by_row_and_column() {
+    let mut board = Board::new();
+    for (row, col) in [(0, 0), (0, 2), (2, 0), (2, 2)] {
+        board.place_rc(row, col).unwrap();
+    }
+    assert_eq!(board.mark_at(0, 0), Some(Mark::X));
+    assert_eq!(board.mark_at(0, 2), Some(Mark::O));
+    assert_eq!(board.mark_at(2, 0), Some(Mark::X));
+    assert_eq!(board.mark_at(2, 2), Some(Mark::O));
+    assert_eq!(board.marks()[8], Some(Mark::O));
+    assert_eq!(board.mark_at(1, 1), None);
+
+    assert_eq!(board.place_rc(3, 0), Err(PlaceError::OutOfRange));
+    assert_eq!(board.place_rc(0, 3), Err(PlaceError::OutOfRange));
+    assert_eq!(board.mark_at(3, 0), None);
+    assert_eq!(board.place_rc(0, 0), Err(PlaceError::Occupied));
+}
+
+#[test]
+fn legal_moves_only_while_playing() {
+    assert_eq!(
+        Board::new().legal_moves().collect::<Vec<_>>(),
+        (0..=8).collect::<Vec<_>>()
+    );
+    let playing: Board = "X...O....".parse().unwrap();
+    assert_eq!(
+        playing.legal_moves().collect::<Vec<_>>(),
+        [1, 2, 3, 5, 6, 7, 8]
+    );
+    let full: Board = "XOXXOOOXX".parse().unwrap();
+    assert_eq!(full.legal_moves().count(), 0);
+    let won: Board = "XXXOO....".parse().unwrap();
+    assert_eq!(won.legal_moves().count(), 0);
+}
+
+#[test]
+fn four_by_four_needs_four_in_a_row() {
+    let mut board = Board::with_size(4, 4);
+    for index in [4, 0, 5, 1, 6, 2] {
+        board.place(index).unwrap();
+    }
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+    board.place(7).unwrap();
+    assert_eq!(board.state(), &State::Won(Mark::X));
+    assert_eq!(board.winning_line(), Some(vec![4, 5, 6, 7]));
+}
+
+#[test]
+fn shorter_win_length_on_larger_board() {
+    let mut board = Board::with_size(5, 3);
+    for index in [6, 0, 12, 1, 18] {
+        board.place(index).unwrap();
+    }
+    assert_eq!(board.winning_line(), Some(vec![6, 12, 18]));
+}
+
+#[test]
+fn parse_and_display_larger_boards() {
+    let board: Board = "XO..XO..X.......".parse().unwrap();
+    assert_eq!(board.size(), 4);
+    assert_eq!(board.win_len(), 4);
+    assert_eq!(board.to_string(), "X|O| | \nX|O| | \nX| | | \n | | | \n");
+    assert_eq!(board.to_string().parse::<Board>(), Ok(board));
+}
+
+#[test]
+fn rotated_positions_share_a_canonical_board() {
+    // The same position turned a quarter clockwise.
+    let board: Board = "XO.\n.X.\n...".parse().unwrap();
+    let rotated: Board = "..X\n.XO\n...".parse().unwrap();
+    assert_ne!(board, rotated);
+    assert_eq!(board.canonical(), rotated.canonical());
+    assert_eq!(board.canonical().canonical(), board.canonical());
+}
+
+#[test]
+fn evaluate_favors_the_side_about_to_win() {
+    let neutral: Board = "X...O....".parse().unwrap();
+    let x_threat: Board = "XX..O....".parse().unwrap();
+    let o_threat: Board = "X..OO..X.".parse().unwrap();
+    assert_eq!(Board::new().evaluate(), 0);
+    assert!(x_threat.evaluate() > neutral.evaluate());
+    assert!(o_threat.evaluate() < 0);
+
+    let won: Board = "XXXOO....".parse().unwrap();
+    assert!(won.evaluate() > x_threat.evaluate());
+    assert_eq!("XOXXOOOXX".parse::<Board>().unwrap().evaluate(), 0);
+}
+
+#[test]
+fn render_colors_only_when_asked() {
+    let board: Board = "XO.......".parse().unwrap();
+    let plain = board.render(RenderOptions::default());
+    assert!(!plain.contains('\x1B'));
+    assert_eq!(plain, board.to_string());
+    let colored = board.render(RenderOptions {
+        color: true,
+        ..RenderOptions::default()
+    });
+    assert!(colored.contains("\x1B[31mX\x1B[0m"));
+    assert!(colored.contains("\x1B[34mO\x1B[0m"));
+}
+
+#[test]
+fn guides_number_only_empty_cells() {
+    let board: Board = "X...O...X".parse().unwrap();
+    let guided = board.render(RenderOptions {
+        guides: true,
+        ..RenderOptions::default()
+    });
+    assert_eq!(guided, "X|1|2\n3|O|5\n6|7|X\n");
+    assert_eq!(board.to_string(), "X| | \n |O| \n | |X\n");
+}
+
+#[test]
+fn styles_draw_the_grid() {
+    let board: Board = "X.O.X...O".parse().unwrap();
+    let style = |style| {
+        board.render(RenderOptions {
+            style,
+            ..RenderOptions::default()
+        })
+    };
+    assert_eq!(style(BoardStyle::Plain), "X| |O\n |X| \n | |O\n");
+    assert_eq!(
+        style(BoardStyle::Boxed),
+        " X │   │ O \n───┼───┼───\n   │ X │   \n───┼───┼───\n   │   │ O \n"
+    );
+    assert_eq!(style(BoardStyle::Compact), "X.O\n.X.\n..O\n");
+    assert_eq!(
+        style(BoardStyle::Compact).trim().parse::<Board>(),
+        Ok(board)
+    );
+}
+
+#[test]
+fn oneline_style_puts_the_rows_on_one_line() {
+    let board: Board = "X.O..XO.X".parse().unwrap();
+    let render = |guides| {
+        board.render(RenderOptions {
+            style: BoardStyle::OneLine,
+            guides,
+            ..RenderOptions::default()
+        })
+    };
+    assert_eq!(render(false), "X.O|..X|O.X\n");
+    assert_eq!(render(true), "X1O|34X|O7X\n");
+    assert_eq!(render(false).trim().parse::<Board>(), Ok(board));
+    let wide = Board::with_dimensions(4, 2, 2);
+    assert_eq!(
+        wide.render(RenderOptions {
+            style: BoardStyle::OneLine,
+            ..RenderOptions::default()
+        }),
+        "....|....\n"
+    );
+}
+
+#[test]
+fn boxed_guides_line_up_past_nine() {
+    let guided = Board::with_size(4, 3).render(RenderOptions {
+        guides: true,
+        style: BoardStyle::Boxed,
+        ..RenderOptions::default()
+    });
+    let lines: Vec<&str> = guided.lines().collect();
+    assert_eq!(lines[0], " 0  │ 1  │ 2  │ 3  ");
+    assert_eq!(lines[1], "────┼────┼────┼────");
+    assert!(lines.iter().all(|l| l.chars().count() == 19));
+}
+
+#[test]
+fn full_tree_has_the_known_outcome_counts() {
+    let outcomes = Board::new().enumerate_outcomes();
+    assert_eq!(
+        outcomes,
+        Outcomes {
+            x_wins: 131_184,
+            o_wins: 77_904,
+            draws: 46_080,
+        }
+    );
+    assert_eq!(outcomes.total(), 255_168);
+}
+
+#[test]
+fn finished_board_is_one_outcome() {
+    let won: Board = "XXXOO....".parse().unwrap();
+    assert_eq!(won.enumerate_outcomes().x_wins, 1);
+    assert_eq!(won.enumerate_outcomes().total(), 1);
+    // O to move: taking 6 draws, taking 8 lets X complete the left column.
+    let late: Board = "XOXXOO.X.".parse().unwrap();
+    assert_eq!(
+        late.enumerate_outcomes(),
+        Outcomes {
+            x_wins: 1,
+            o_wins: 0,
+            draws: 1,
+        }
+    );
+}
+
+// Checks that every tag is closed in order, which is all the SVG output needs.
+fn assert_balanced(xml: &str) {
+    let mut open = Vec::new();
+    for tag in xml.split('<').skip(1) {
+        let tag = &tag[..tag.find('>').unwrap()];
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop(), Some(name), "in {}", xml);
+        } else if !tag.ends_with('/') {
+            open.push(tag.split_whitespace().next().unwrap());
+        }
+    }
+    assert!(open.is_empty(), "unclosed {:?}", open);
+}
+
+#[test]
+fn svg_draws_grid_and_marks() {
+    let board: Board = "X.O.X....".parse().unwrap();
+    let svg = board.to_svg();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_balanced(&svg);
+    assert_eq!(svg.matches("<line").count(), 4);
+    assert_eq!(svg.matches("<text").count(), 3);
+    assert!(svg.contains(">X</text>") && svg.contains(">O</text>"));
+}
+
+#[test]
+fn svg_strikes_through_the_win() {
+    let won = Board::from_moves(&[0, 3, 4, 5, 8]).unwrap();
+    let svg = won.to_svg();
+    assert_balanced(&svg);
+    assert_eq!(svg.matches("<line").count(), 5);
+    assert!(svg.contains(r#"<line x1="50" y1="50" x2="250" y2="250""#));
+    assert_eq!(Board::with_size(4, 3).to_svg().matches("<line").count(), 6);
+}
+
+#[test]
+fn four_quarter_turns_are_the_identity() {
+    let board = Board::from_moves(&[0, 4, 5]).unwrap();
+    let turned = board.rotated_90().rotated_90().rotated_90().rotated_90();
+    assert_eq!(turned, board);
+    assert_eq!(board.rotated_90().rotated_90(), board.rotated_180());
+    assert_eq!(board.rotated_90().rotated_180(), board.rotated_270());
+    assert_eq!(board.mirrored_horizontal().mirrored_horizontal(), board);
+}
+
+#[test]
+fn named_symmetries_move_cells() {
+    let board: Board = "XO.......".parse().unwrap();
+    assert_eq!(board.rotated_90(), "..X\n..O\n...".parse().unwrap());
+    assert_eq!(
+        board.mirrored_horizontal(),
+        ".OX\n...\n...".parse().unwrap()
+    );
+    assert_eq!(board.mirrored_vertical(), "...\n...\nXO.".parse().unwrap());
+    assert_eq!(board.transposed(), "X..\nO..\n...".parse().unwrap());
+    assert_eq!(board.anti_transposed(), "...\n..O\n..X".parse().unwrap());
+}
+
+#[test]
+fn quarter_turn_makes_a_row_win_a_column_win() {
+    let won = Board::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+    let turned = won.rotated_90();
+    assert_eq!(turned.state(), &State::Won(Mark::X));
+    for i in [2, 5, 8] {
+        assert_eq!(turned.marks()[i], Some(Mark::X));
+    }
+    assert_eq!(turned.marks()[0], None);
+}
+
+#[test]
+fn symmetries_cover_all_eight() {
+    let board = Board::from_moves(&[0, 5]).unwrap();
+    let all = board.symmetries();
+    assert_eq!(all[0], board);
+    for (i, a) in all.iter().enumerate() {
+        assert_eq!(a.canonical(), board.canonical());
+        for b in &all[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+    assert!(all.contains(&board.transposed()));
+}
+
+#[test]
+fn encode_round_trips_random_games() {
+    let mut rng = Rng::new(11);
+    for _ in 0..2000 {
+        let first = if rng.below(2) == 0 { Mark::X } else { Mark::O };
+        let mut board = Board::with_first_player(first);
+        for _ in 0..rng.below(10) {
+            let moves: Vec<usize> = board.legal_moves().collect();
+            if moves.is_empty() {
+                break;
+            }
+            board.place(moves[rng.below(moves.len())]).unwrap();
+        }
+        let code = board.encode();
+        assert!(code < 1 << 19);
+        let decoded = Board::decode(code).unwrap();
+        assert_eq!(decoded, board);
+        assert_eq!(decoded.state(), board.state());
+        assert_eq!(decoded.encode(), code);
+    }
+}
+
+#[test]
+fn marks_array_round_trips_through_from_marks() {
+    let mut rng = Rng::new(5);
+    for _ in 0..500 {
+        let mut board = Board::new();
+        for _ in 0..rng.below(10) {
+            let moves: Vec<usize> = board.legal_moves().collect();
+            if moves.is_empty() {
+                break;
+            }
+            board.place(moves[rng.below(moves.len())]).unwrap();
+        }
+        let marks = board.marks_array();
+        assert_eq!(&marks[..], board.marks());
+        let rebuilt = Board::from_marks(marks).unwrap();
+        assert_eq!(rebuilt, board);
+        assert_eq!(rebuilt.state(), board.state());
+        assert_eq!(rebuilt.marks_array(), marks);
+    }
+    // With equal counts the array can't say who went first, so X moves.
+    let mut o_first = Board::with_first_player(Mark::O);
+    o_first.place(4).unwrap();
+    o_first.place(0).unwrap();
+    let rebuilt = Board::from_marks(o_first.marks_array()).unwrap();
+    assert!(rebuilt.same_marks(&o_first));
+    assert_eq!(rebuilt.state(), &State::Turn(Mark::X));
+}
+
+#[test]
+fn from_marks_rejects_impossible_arrays() {
+    let (x, o) = (Some(Mark::X), Some(Mark::O));
+    assert_eq!(
+        Board::from_marks([x, x, None, None, None, None, None, None, None]),
+        Err(BoardError::MarkCount)
+    );
+    assert_eq!(
+        Board::from_marks([x, x, x, o, o, o, None, None, None]),
+        Err(BoardError::BothWon)
+    );
+    assert_eq!(Board::from_marks([None; 9]), Ok(Board::new()));
+}
+
+#[test]
+#[should_panic(expected = "more than 256 cells")]
+fn boards_past_max_cells_are_refused() {
+    Board::with_size(17, 3);
+}
+
+#[test]
+fn the_largest_board_searches_every_cell() {
+    let side = 16;
+    assert_eq!(side * side, MAX_CELLS);
+    // Every row but the last, alternating marks, so nobody has a line yet.
+    let mut board = Board::with_size(side, side);
+    for index in 0..MAX_CELLS - side {
+        board.place(index).unwrap();
+    }
+    let mve = best_move_with_depth(&board, 1).unwrap() as usize;
+    assert!((MAX_CELLS - side..MAX_CELLS).contains(&mve));
+}
+
+#[test]
+#[should_panic]
+fn marks_array_needs_nine_cells() {
+    Board::with_size(4, 3).marks_array();
+}
+
+#[test]
+fn decode_rejects_codes_encode_never_makes() {
+    assert_eq!(Board::decode(0), Some(Board::new()));
+    assert_eq!(
+        Board::decode(1 << 18),
+        Some(Board::with_first_player(Mark::O))
+    );
+    // A cell holding the unused value 3.
+    assert_eq!(Board::decode(3), None);
+    // Bits past the side to move.
+    assert_eq!(Board::decode(1 << 19), None);
+    // Two X and no O.
+    assert_eq!(Board::decode(0b0101), None);
+    // X's top row is complete, so nobody is to move.
+    let won = Board::from_moves(&[0, 3, 1, 4, 2]).unwrap().encode();
+    assert!(Board::decode(won).is_some());
+    assert_eq!(Board::decode(won | 1 << 18), None);
+}
+
+#[test]
+fn misere_hands_the_game_to_whoever_didnt_complete_the_line() {
+    let mut board = Board::new();
+    board.set_variant(Variant::Misere);
+    for index in [0, 3, 1, 4, 2] {
+        board.place(index).unwrap();
+    }
+    assert_eq!(board.state(), &State::Won(Mark::O));
+    assert_eq!(board.winning_line(), Some(vec![0, 1, 2]));
+    assert!(board.evaluate() < 0);
+
+    board.set_variant(Variant::Standard);
+    assert_eq!(board.state(), &State::Won(Mark::X));
+    assert_ne!(board, {
+        let mut other = board.clone();
+        other.set_variant(Variant::Misere);
+        other
+    });
+}
+
+#[test]
+fn misere_evaluation_counts_open_lines_against() {
+    let standard = Board::from_moves(&[4]).unwrap();
+    let mut misere = standard.clone();
+    misere.set_variant(Variant::Misere);
+    assert_eq!(misere.evaluate(), -standard.evaluate());
+}
+
+#[test]
+fn gravity_drops_marks_to_the_bottom_of_the_column() {
+    let mut board = Board::with_gravity(4, 5, 3);
+    assert_eq!(board.place_column(1), Ok(17));
+    assert_eq!(board.mark_at(4, 1), Some(Mark::X));
+    assert_eq!(board.place_column(1), Ok(13));
+    assert_eq!(board.mark_at(3, 1), Some(Mark::O));
+    assert_eq!(board.legal_moves().collect::<Vec<_>>(), vec![9, 16, 18, 19]);
+    assert_eq!(board.place(2), Err(PlaceError::Unsupported));
+    assert_eq!(board.place_column(4), Err(PlaceError::OutOfRange));
+
+    for _ in 0..3 {
+        board.place_column(1).unwrap();
+    }
+    assert_eq!(board.place_column(1), Err(PlaceError::Occupied));
+    assert_eq!(board.validate(), Ok(()));
+}
+
+#[test]
+fn gravity_detects_a_vertical_connect() {
+    let mut board = Board::with_gravity(4, 5, 3);
+    for col in [0, 1, 0, 1, 0] {
+        board.place_column(col).unwrap();
+    }
+    assert_eq!(board.state(), &State::Won(Mark::X));
+    assert_eq!(board.winning_line(), Some(vec![8, 12, 16]));
+}
+
+#[test]
+fn rectangular_boards_render_every_row() {
+    let mut board = Board::with_dimensions(4, 2, 2);
+    board.place_rc(1, 3).unwrap();
+    assert_eq!(board.to_string(), " | | | \n | | |X\n");
+    assert_eq!(board.place_rc(2, 0), Err(PlaceError::OutOfRange));
+    let mirrored = board.mirrored_horizontal();
+    assert_eq!(mirrored.mark_at(1, 0), Some(Mark::X));
+    assert_eq!(mirrored.canonical(), board.canonical());
+}
+
+#[test]
+fn cells_can_be_read_by_indexing() {
+    let board = Board::from_moves(&[4, 0, 8]).unwrap();
+    assert_eq!(board[4], Some(Mark::X));
+    assert_eq!(board[0], Some(Mark::O));
+    assert_eq!(board[8], Some(Mark::X));
+    assert_eq!(board[1], None);
+    assert_eq!(board[(1, 1)], Some(Mark::X));
+    assert_eq!(board[(0, 0)], Some(Mark::O));
+    assert_eq!(board[(2, 2)], board[8]);
+    for (i, &cell) in board.marks().iter().enumerate() {
+        assert_eq!(board[i], cell);
+        assert_eq!(board[(i / 3, i % 3)], board.mark_at(i / 3, i % 3));
+    }
+}
+
+#[test]
+fn cells_pair_each_index_with_its_mark() {
+    let board = Board::from_moves(&[4, 0, 8]).unwrap();
+    let x = Some(Mark::X);
+    let o = Some(Mark::O);
+    assert_eq!(
+        board.cells().collect::<Vec<_>>(),
+        vec![
+            (0, o),
+            (1, None),
+            (2, None),
+            (3, None),
+            (4, x),
+            (5, None),
+            (6, None),
+            (7, None),
+            (8, x),
+        ]
+    );
+    let mut taken = Vec::new();
+    for (i, mark) in &board {
+        if mark.is_some() {
+            taken.push(i);
+        }
+    }
+    assert_eq!(taken, vec![0, 4, 8]);
+    assert_eq!(Board::with_dimensions(4, 3, 3).cells().count(), 12);
+}
+
+#[test]
+#[should_panic]
+fn indexing_a_column_past_the_edge_panics() {
+    let _ = Board::new()[(0, 3)];
+}
+
+#[test]
+fn themes_change_the_glyphs_but_not_the_marks() {
+    let theme = Theme {
+        first: '🐱',
+        second: '🐶',
+    };
+    let board = Board::from_moves(&[0, 4, 8]).unwrap();
+    let drawn = board.render(RenderOptions {
+        theme,
+        ..RenderOptions::default()
+    });
+    assert_eq!(drawn, "🐱| | \n |🐶| \n | |🐱\n");
+    assert!(!drawn.contains('X'));
+
+    assert_eq!(Board::parse_with_theme(&drawn, theme), Ok(board.clone()));
+    assert_eq!(
+        Board::parse_with_theme("🐱|.|.\n.|O|.\n.|.|X", theme),
+        Ok(board)
+    );
+    assert_eq!(
+        Board::parse_with_theme(&drawn, Theme::default()),
+        Err(ParseBoardError::InvalidChar('🐱'))
+    );
+}
+
+#[test]
+fn same_marks_ignores_the_side_to_move() {
+    let x_first = Board::new();
+    let o_first = Board::with_first_player(Mark::O);
+    assert!(x_first.same_marks(&o_first));
+    assert_ne!(x_first, o_first);
+
+    // X in the corner and O in the center, reached with either side going first.
+    let mut a = Board::with_first_player(Mark::O);
+    a.place(4).unwrap();
+    a.place(0).unwrap();
+    let b = Board::from_moves(&[0, 4]).unwrap();
+    assert_eq!(a.current_player(), Some(Mark::O));
+    assert_eq!(b.current_player(), Some(Mark::X));
+    assert!(a.same_marks(&b));
+    assert_ne!(a, b);
+
+    assert!(!a.same_marks(&Board::from_moves(&[4, 0]).unwrap()));
+    assert!(!Board::new().same_marks(&Board::with_size(4, 3)));
+}
@@ -0,0 +1,41 @@
+use tttai::{best_move, Board, Mark, MiniMaxNode, OpeningBook, State};
+
+#[test]
+fn empty_board_opens_in_the_center() {
+    let book = OpeningBook::build(1);
+    assert_eq!(book.lookup(&Board::new()), Some(4));
+    assert_eq!(book.lookup(&Board::new()), best_move(&Board::new()));
+}
+
+#[test]
+fn book_agrees_with_search() {
+    let book = OpeningBook::build(3);
+    for a in 0..9 {
+        for b in (0..9).filter(|&b| b != a) {
+            let board = Board::from_moves(&[a, b]).unwrap();
+            let searched = MiniMaxNode::new(&board).calculate();
+            assert_eq!(book.lookup(&board), Some(searched), "after {} {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn stores_one_entry_per_symmetry_class() {
+    assert_eq!(OpeningBook::build(4).len(), 1 + 3 + 12 + 38);
+}
+
+#[test]
+fn positions_outside_the_book_miss() {
+    let book = OpeningBook::build(2);
+    let board = Board::from_moves(&[4, 0]).unwrap();
+    assert_eq!(*board.state(), State::Turn(Mark::X));
+    assert_eq!(book.lookup(&board), None);
+    assert_eq!(book.lookup(&Board::with_size(4, 3)), None);
+}
+
+#[test]
+fn standard_book_is_the_four_ply_build() {
+    let standard = OpeningBook::standard();
+    assert_eq!(standard.len(), 54);
+    assert_eq!(standard, &OpeningBook::build(4));
+}
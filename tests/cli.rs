@@ -0,0 +1,812 @@
+use std::ffi::OsStr;
+use std::io::{BufReader, ErrorKind};
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+use tttai::cli::{
+    clear_screen, handle_input, handle_numpad_input, numpad_index, numpad_key, parse_move,
+    play_game, read_input_line, read_move, solve_positions, step_through, use_color, InputLine,
+    Mistake, Opponent, Options, Response, Scoreboard, Screen, Summary, Verdict, MAX_INVALID_INPUTS,
+    MAX_LINE_LEN,
+};
+use tttai::{
+    best_move, Board, BoardStyle, Mark, MiniMaxStrategy, PlaceError, RandomStrategy, State,
+    Strategy, Theme, Transcript, Variant, MAX_THINK_DELAY,
+};
+
+fn parse(args: &[&str]) -> Result<Options, String> {
+    Options::parse(args.iter().map(|a| a.to_string()))
+}
+
+#[test]
+fn mark_flag_selects_human_side() {
+    assert_eq!(parse(&[]).unwrap().human, Mark::X);
+    assert_eq!(parse(&["--mark", "O"]).unwrap().human, Mark::O);
+    assert_eq!(parse(&["--mark", "x"]).unwrap().human, Mark::X);
+    assert!(parse(&["--mark", "Z"]).is_err());
+    assert!(parse(&["--mark"]).is_err());
+}
+
+#[test]
+fn selfplay_flag() {
+    assert!(!parse(&[]).unwrap().selfplay);
+    assert!(parse(&["--selfplay"]).unwrap().selfplay);
+}
+
+#[test]
+fn opponent_flag() {
+    assert_eq!(parse(&[]).unwrap().opponent, Opponent::MiniMax);
+    assert_eq!(
+        parse(&["--opponent", "random"]).unwrap().opponent,
+        Opponent::Random
+    );
+    assert_eq!(
+        parse(&["--opponent", "random-opening"]).unwrap().opponent,
+        Opponent::RandomOpening
+    );
+    assert!(parse(&["--opponent", "perfect"]).is_err());
+}
+
+#[test]
+fn seed_flag() {
+    assert_eq!(parse(&[]).unwrap().seed, None);
+    assert_eq!(parse(&["--seed", "42"]).unwrap().seed, Some(42));
+    assert!(parse(&["--seed", "abc"]).is_err());
+}
+
+#[test]
+fn replay_subcommand() {
+    let options = parse(&["replay", "game.txt"]).unwrap();
+    assert_eq!(options.replay.as_deref(), Some("game.txt"));
+    assert_eq!(parse(&[]).unwrap().replay, None);
+    assert!(parse(&["replay"]).is_err());
+}
+
+#[test]
+fn step_through_goes_forward_and_back() {
+    let boards = "X4 O0 X8"
+        .parse::<Transcript>()
+        .unwrap()
+        .positions()
+        .unwrap();
+    let mut out = Vec::new();
+    step_through(
+        &boards,
+        &mut "\n\nb\nx\nq\n".as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let steps: Vec<&str> = out.lines().filter(|l| l.starts_with("Move ")).collect();
+    assert_eq!(
+        steps,
+        [
+            "Move 0 of 3",
+            "Move 1 of 3",
+            "Move 2 of 3",
+            "Move 1 of 3",
+            "Move 1 of 3"
+        ]
+    );
+    assert!(out.contains(&boards[2].to_string()));
+    assert!(out.contains("Press Enter, b or q."));
+}
+
+#[test]
+fn step_through_ends_after_the_last_board() {
+    let boards = "X4 O0 X8 O2 X1 O7 X6 O3 X5"
+        .parse::<Transcript>()
+        .unwrap()
+        .positions()
+        .unwrap();
+    let mut out = Vec::new();
+    step_through(
+        &boards,
+        &mut "\n".repeat(20).as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("Move ").count(), 10);
+    assert!(out.contains("Tie!"));
+}
+
+#[test]
+fn solve_subcommand() {
+    let options = parse(&["solve", "positions.txt", "--csv"]).unwrap();
+    assert_eq!(options.solve.as_deref(), Some("positions.txt"));
+    assert!(options.csv);
+    assert_eq!(parse(&[]).unwrap().solve, None);
+    assert!(parse(&["solve"]).is_err());
+}
+
+#[test]
+fn verbose_flag() {
+    assert!(!parse(&[]).unwrap().verbose);
+    assert!(parse(&["--verbose"]).unwrap().verbose);
+}
+
+#[test]
+fn glyphs_flag_sets_the_theme() {
+    let options = parse(&["--glyphs", "🐱🐶", "--mark", "🐶"]).unwrap();
+    assert_eq!(
+        options.theme,
+        Theme {
+            first: '🐱',
+            second: '🐶'
+        }
+    );
+    assert_eq!(options.human, Mark::O);
+    assert_eq!(
+        parse(&["--mark", "o", "--glyphs", "ab"]).unwrap().human,
+        Mark::O
+    );
+    assert!(parse(&["--glyphs", "a"]).is_err());
+    assert!(parse(&["--glyphs", "aa"]).is_err());
+    assert!(parse(&["--mark", "🐶"]).is_err());
+}
+
+#[test]
+fn think_delay_flag() {
+    assert_eq!(parse(&[]).unwrap().think_delay, Duration::ZERO);
+    assert_eq!(
+        parse(&["--think-delay-ms", "250"]).unwrap().think_delay,
+        Duration::from_millis(250)
+    );
+    assert_eq!(
+        parse(&["--think-delay-ms", "999999"]).unwrap().think_delay,
+        MAX_THINK_DELAY
+    );
+    assert!(parse(&["--think-delay-ms", "soon"]).is_err());
+}
+
+#[test]
+fn bot_waits_out_the_think_delay() {
+    let screen = Screen {
+        think_delay: Duration::from_millis(20),
+        ..Screen::default()
+    };
+    let start = Instant::now();
+    play_game(
+        Board::new(),
+        Mark::X,
+        &mut Scripted(vec![3, 4]),
+        &mut "0\n1\n2\n".as_bytes(),
+        &mut Vec::new(),
+        screen,
+    )
+    .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(40));
+}
+
+#[test]
+fn misere_flag() {
+    assert_eq!(parse(&[]).unwrap().variant, Variant::Standard);
+    assert_eq!(parse(&["--misere"]).unwrap().variant, Variant::Misere);
+}
+
+#[test]
+fn numpad_flag() {
+    assert!(!parse(&[]).unwrap().numpad);
+    assert!(parse(&["--numpad"]).unwrap().numpad);
+}
+
+#[test]
+fn numpad_keys_map_to_indices() {
+    let expected = [
+        (7, 0),
+        (8, 1),
+        (9, 2),
+        (4, 3),
+        (5, 4),
+        (6, 5),
+        (1, 6),
+        (2, 7),
+        (3, 8),
+    ];
+    for (key, index) in expected {
+        let key = char::from_digit(key, 10).unwrap();
+        assert_eq!(numpad_index(key), Some(index), "key {}", key);
+    }
+    assert_eq!(numpad_index('0'), None);
+    for index in 0..9 {
+        let key = numpad_key(index).unwrap();
+        assert_eq!(numpad_index(key), Some(index));
+    }
+    assert_eq!(numpad_key(9), None);
+    assert_eq!(numpad_index('x'), None);
+}
+
+#[test]
+fn numpad_input_places_and_hints() {
+    let mut board = Board::new();
+    assert_eq!(handle_numpad_input(&mut board, "7\n"), Response::Placed(0));
+    assert_eq!(board.marks()[0], Some(Mark::X));
+    assert_eq!(
+        handle_numpad_input(&mut board, "7"),
+        Response::Invalid(PlaceError::Occupied)
+    );
+    assert_eq!(handle_numpad_input(&mut board, "hint"), Response::Hint(4));
+    assert_eq!(
+        handle_numpad_input(&mut board, "1 1"),
+        Response::Unrecognized
+    );
+    assert_eq!(
+        handle_numpad_input(&mut board, "10"),
+        Response::Unrecognized
+    );
+}
+
+#[test]
+fn guides_flag() {
+    assert!(!parse(&[]).unwrap().guides);
+    assert!(parse(&["--guides"]).unwrap().guides);
+}
+
+#[test]
+fn style_flag() {
+    assert_eq!(parse(&[]).unwrap().style, BoardStyle::Plain);
+    assert_eq!(
+        parse(&["--style", "boxed"]).unwrap().style,
+        BoardStyle::Boxed
+    );
+    assert_eq!(
+        parse(&["--style", "compact"]).unwrap().style,
+        BoardStyle::Compact
+    );
+    assert_eq!(
+        parse(&["--style", "oneline"]).unwrap().style,
+        BoardStyle::OneLine
+    );
+    assert_eq!(parse(&["--oneline"]).unwrap().style, BoardStyle::OneLine);
+    assert!(parse(&["--style", "fancy"]).is_err());
+    assert!(parse(&["--style"]).is_err());
+}
+
+#[test]
+fn alternate_flag() {
+    assert!(!parse(&[]).unwrap().alternate);
+    assert!(parse(&["--alternate"]).unwrap().alternate);
+}
+
+#[test]
+fn scoreboard_totals_results() {
+    let mut score = Scoreboard::default();
+    for state in [
+        State::Won(Mark::O),
+        State::Tie,
+        State::Won(Mark::X),
+        State::Tie,
+        State::Turn(Mark::X),
+        State::Won(Mark::O),
+        State::Tie,
+    ] {
+        score.record(&state, Mark::O);
+    }
+    assert_eq!(
+        score,
+        Scoreboard {
+            human: 2,
+            bot: 1,
+            ties: 3
+        }
+    );
+    assert_eq!(score.to_string(), "You: 2  Bot: 1  Ties: 3");
+}
+
+#[test]
+fn hint_suggests_without_moving() {
+    let mut board = Board::new();
+    board.place(4).unwrap();
+    let before = board.clone();
+    let hint = best_move(&board).unwrap();
+    assert_eq!(handle_input(&mut board, "hint\n"), Response::Hint(hint));
+    assert_eq!(board, before);
+    assert_eq!(board.state(), &State::Turn(Mark::O));
+}
+
+#[test]
+fn input_places_or_reports() {
+    let mut board = Board::new();
+    assert_eq!(handle_input(&mut board, "4\n"), Response::Placed(4));
+    assert_eq!(board.mark_at(1, 1), Some(Mark::X));
+    assert_eq!(
+        handle_input(&mut board, "4"),
+        Response::Invalid(PlaceError::Occupied)
+    );
+    assert_eq!(handle_input(&mut board, "middle"), Response::Unrecognized);
+    assert_eq!(board.state(), &State::Turn(Mark::O));
+}
+
+#[test]
+fn color_respects_flag_and_no_color() {
+    let options = parse(&[]).unwrap();
+    assert!(use_color(&options, None));
+    assert!(use_color(&options, Some(OsStr::new(""))));
+    assert!(!use_color(&options, Some(OsStr::new("1"))));
+    assert!(!use_color(&parse(&["--no-color"]).unwrap(), None));
+}
+
+#[test]
+fn clear_screen_only_escapes_on_a_terminal() {
+    let mut out = Vec::new();
+    clear_screen(&mut out, true).unwrap();
+    assert_eq!(out, b"\x1B[2J\x1B[1;1H");
+    let mut out = Vec::new();
+    clear_screen(&mut out, false).unwrap();
+    assert_eq!(out, b"\n");
+}
+
+// Plays the given moves in order and fails the test if asked for more.
+struct Scripted(Vec<usize>);
+
+impl Strategy for Scripted {
+    fn choose(&mut self, _board: &Board) -> usize {
+        assert!(
+            !self.0.is_empty(),
+            "bot asked to move after the script ran out"
+        );
+        self.0.remove(0)
+    }
+}
+
+#[test]
+fn game_ends_on_the_winning_move() {
+    let mut bot = Scripted(vec![3, 4]);
+    let mut input = "0\n1\n2\n".as_bytes();
+    let mut out = Vec::new();
+    let board = play_game(
+        Board::new(),
+        Mark::X,
+        &mut bot,
+        &mut input,
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap()
+    .board;
+    assert_eq!(board.state(), &State::Won(Mark::X));
+    assert_eq!(board.move_count(), 5);
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("Bot played 3: Taking an edge.\n"));
+    assert!(
+        out.contains("Bot played 4: Taking the center, where X could have made a double threat.\n")
+    );
+    assert!(out.ends_with(
+        "X Won! You beat the bot.\nGame over after 5 moves. You played optimally throughout.\n"
+    ));
+}
+
+#[test]
+fn summary_flags_a_move_that_throws_away_a_win() {
+    // O's edge reply loses, but X's 7 lets O hold the draw where 0 would have won.
+    let mut bot = Scripted(vec![1, 0, 6, 5]);
+    let mut input = "4\n7\n2\n3\n8\n".as_bytes();
+    let mut out = Vec::new();
+    let board = play_game(
+        Board::new(),
+        Mark::X,
+        &mut bot,
+        &mut input,
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap()
+    .board;
+    let summary = Summary::new(&board, Mark::X);
+    assert_eq!(summary.state, State::Tie);
+    assert_eq!(summary.moves, 9);
+    assert_eq!(
+        summary.mistakes,
+        vec![Mistake {
+            move_number: 3,
+            played: 7,
+            best: 0
+        }]
+    );
+    let out = String::from_utf8(out).unwrap();
+    assert!(
+        out.ends_with("Tie!\nGame over after 9 moves.\nMove 3: you played 7, but 0 was better.\n")
+    );
+
+    // Seen from O's side, only the losing edge reply counts.
+    let mistakes = Summary::new(&board, Mark::O).mistakes;
+    assert_eq!(mistakes.len(), 1);
+    assert_eq!((mistakes[0].move_number, mistakes[0].played), (2, 1));
+}
+
+#[test]
+fn games_only_end_with_the_board_finished_or_input_gone() {
+    // Enough input to fill the board whatever the bot leaves free.
+    let script = "0\n1\n2\n3\n4\n5\n6\n7\n8\n";
+    for seed in 0..50 {
+        for human in [Mark::X, Mark::O] {
+            let board = play_game(
+                Board::new(),
+                human,
+                &mut RandomStrategy::new(seed),
+                &mut script.as_bytes(),
+                &mut Vec::new(),
+                Screen::default(),
+            )
+            .unwrap()
+            .board;
+            assert!(board.is_over(), "seed {} as {}:\n{}", seed, human, board);
+        }
+    }
+}
+
+#[test]
+fn resigning_loses_the_game() {
+    let mut out = Vec::new();
+    let played = play_game(
+        Board::new(),
+        Mark::X,
+        &mut Scripted(vec![]),
+        &mut "resign\n".as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    assert_eq!(played.result, Some(State::Won(Mark::O)));
+    assert_eq!(played.board, Board::new());
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("You resigned.\nO Won! The bot wins.\n"));
+
+    let mut score = Scoreboard::default();
+    score.record(&played.result.unwrap(), Mark::X);
+    assert_eq!(score.bot, 1);
+}
+
+#[test]
+fn draw_offers_are_accepted_only_in_drawn_positions() {
+    let mut out = Vec::new();
+    let played = play_game(
+        Board::from_moves(&[4, 0]).unwrap(),
+        Mark::X,
+        &mut Scripted(vec![]),
+        &mut "draw\n".as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    assert_eq!(played.result, Some(State::Tie));
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains("The bot accepts the draw.\nTie!\n"));
+
+    // After O's edge reply X is winning, so the bot won't let O off.
+    let mut out = Vec::new();
+    let played = play_game(
+        Board::from_moves(&[4, 1, 0]).unwrap(),
+        Mark::O,
+        &mut Scripted(vec![]),
+        &mut "draw\n".as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    assert_eq!(played.result, None);
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains("The bot declines the draw.\n"));
+}
+
+#[test]
+fn resign_and_draw_are_recognized() {
+    let mut board = Board::new();
+    assert_eq!(handle_input(&mut board, "resign\n"), Response::Resign);
+    assert_eq!(handle_input(&mut board, " DRAW "), Response::OfferDraw);
+    assert_eq!(handle_numpad_input(&mut board, "draw"), Response::OfferDraw);
+    assert_eq!(board, Board::new());
+}
+
+#[test]
+fn end_of_input_stops_cleanly() {
+    let mut board = Board::new();
+    let mut out = Vec::new();
+    let read = read_move(
+        &mut board,
+        Mark::X,
+        &mut "".as_bytes(),
+        &mut out,
+        Screen::default(),
+    );
+    assert_eq!(read.unwrap(), None);
+    assert_eq!(board, Board::new());
+
+    let mut out = Vec::new();
+    let board = play_game(
+        Board::new(),
+        Mark::X,
+        &mut Scripted(vec![]),
+        &mut "hint\n".as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap()
+    .board;
+    assert_eq!(board, Board::new());
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .ends_with("No more input, exiting.\n"));
+}
+
+#[test]
+fn moves_parse_as_index_or_row_and_column() {
+    assert_eq!(parse_move("4"), Some(4));
+    assert_eq!(parse_move("1 1"), Some(4));
+    assert_eq!(parse_move("2,0\n"), Some(6));
+    assert_eq!(parse_move(" 0 , 2 "), Some(2));
+    assert_eq!(parse_move("3 0"), None);
+    assert_eq!(parse_move("one two"), None);
+    assert_eq!(parse_move("1 2 3"), None);
+    assert_eq!(parse_move(""), None);
+}
+
+#[test]
+fn solve_skips_bad_lines() {
+    let input = "XX.OO....\nXXZ\n\nXXXOO....\n";
+    let (mut out, mut errors) = (Vec::new(), Vec::new());
+    solve_positions(input.as_bytes(), &mut out, &mut errors, true).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let errors = String::from_utf8(errors).unwrap();
+    let mut rows = out.lines();
+    assert_eq!(rows.next(), Some("line,board,value,best_move"));
+    let row = rows.next().unwrap();
+    assert!(row.starts_with("1,XX.OO....,"));
+    assert!(row.ends_with(",2"));
+    assert!(rows.next().unwrap().starts_with("4,XXXOO....,"));
+    assert_eq!(rows.next(), None);
+    assert!(errors.starts_with("line 2: "));
+    assert_eq!(errors.lines().count(), 1);
+
+    let mut out = Vec::new();
+    solve_positions("XX.OO....".as_bytes(), &mut out, &mut Vec::new(), false).unwrap();
+    assert!(String::from_utf8(out).unwrap().ends_with("Best move: 2\n"));
+}
+
+#[test]
+fn input_flag() {
+    assert_eq!(parse(&[]).unwrap().input, None);
+    assert_eq!(
+        parse(&["--input", "moves.txt"]).unwrap().input,
+        Some("moves.txt".to_string())
+    );
+    assert!(parse(&["--input"]).is_err());
+}
+
+#[test]
+fn scripted_moves_file_drives_the_game() {
+    let path = env::temp_dir().join(format!("tttai-moves-{}.txt", process::id()));
+    fs::write(&path, "0\n0 1\n2,0\n").unwrap();
+    let mut input = BufReader::new(fs::File::open(&path).unwrap());
+    let mut out = Vec::new();
+    let played = play_game(
+        Board::new(),
+        Mark::X,
+        &mut MiniMaxStrategy::new(),
+        &mut input,
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    fs::remove_file(&path).unwrap();
+    // The script ran out before the game did.
+    assert_eq!(played.result, None);
+    assert_eq!(played.board.to_string(), "X|X|O\nO|O| \nX| | \n");
+    assert_eq!(
+        String::from_utf8(out)
+            .unwrap()
+            .matches("Bot played")
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn overlong_lines_are_skipped_without_being_kept() {
+    let long = "4".repeat(1 << 20);
+    let text = format!("{}\n{}\n4\n{}", long, "x".repeat(MAX_LINE_LEN), long);
+    let mut input = text.as_bytes();
+    assert_eq!(
+        read_input_line(&mut input).unwrap(),
+        Some(InputLine::TooLong)
+    );
+    assert_eq!(
+        read_input_line(&mut input).unwrap(),
+        Some(InputLine::Text("x".repeat(MAX_LINE_LEN)))
+    );
+    assert_eq!(
+        read_input_line(&mut input).unwrap(),
+        Some(InputLine::Text("4".into()))
+    );
+    // The last line has no newline and still counts.
+    assert_eq!(
+        read_input_line(&mut input).unwrap(),
+        Some(InputLine::TooLong)
+    );
+    assert_eq!(read_input_line(&mut input).unwrap(), None);
+}
+
+#[test]
+fn invalid_utf8_is_replaced_rather_than_an_error() {
+    let mut input: &[u8] = b"\xff4\r\n";
+    assert_eq!(
+        read_input_line(&mut input).unwrap(),
+        Some(InputLine::Text("\u{FFFD}4\r".into()))
+    );
+}
+
+#[test]
+fn read_move_explains_an_overlong_line_and_keeps_asking() {
+    let text = format!("{}\n4\n", "4".repeat(10_000));
+    let mut board = Board::new();
+    let mut out = Vec::new();
+    let response = read_move(
+        &mut board,
+        Mark::X,
+        &mut text.as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    assert_eq!(response, Some(Response::Placed(4)));
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("That line is too long to be a move.\n"));
+}
+
+#[test]
+fn read_move_gives_up_after_too_many_invalid_answers() {
+    let read = |text: String| {
+        let mut board = Board::from_moves(&[4]).unwrap();
+        read_move(
+            &mut board,
+            Mark::O,
+            &mut text.as_bytes(),
+            &mut Vec::new(),
+            Screen::default(),
+        )
+    };
+    let nonsense = "banana\n4\n".repeat(MAX_INVALID_INPUTS / 2);
+    let err = read(nonsense.clone() + "0\n").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // One short of the limit, with hints in between, still gets to play.
+    let nonsense = "banana\nhint\n".repeat(MAX_INVALID_INPUTS - 1);
+    assert_eq!(read(nonsense + "0\n").unwrap(), Some(Response::Placed(0)));
+}
+
+#[test]
+fn eval_flag() {
+    assert!(!parse(&[]).unwrap().eval);
+    assert!(parse(&["--eval"]).unwrap().eval);
+}
+
+#[test]
+fn verdict_is_for_the_player_to_move() {
+    // X to move can win on the top row.
+    let mate = Board::from_moves(&[0, 3, 1, 4]).unwrap();
+    assert_eq!(Verdict::of(&mate), Some(Verdict::Win));
+    // O to move can't stop both of X's threats.
+    let lost = Board::from_moves(&[0, 4, 8, 2, 6]).unwrap();
+    assert_eq!(Verdict::of(&lost), Some(Verdict::Loss));
+    assert_eq!(Verdict::of(&Board::new()), Some(Verdict::Draw));
+    let dead: Board = "XOX\nXOO\nOX.".parse().unwrap();
+    assert_eq!(Verdict::of(&dead), Some(Verdict::Draw));
+    let won = Board::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+    assert_eq!(Verdict::of(&won), None);
+}
+
+#[test]
+fn eval_shows_the_verdict_with_each_prompt() {
+    let screen = Screen {
+        eval: true,
+        ..Screen::default()
+    };
+    let mut board = Board::from_moves(&[0, 3, 1, 4]).unwrap();
+    let mut out = Vec::new();
+    read_move(&mut board, Mark::X, &mut "2\n".as_bytes(), &mut out, screen).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("Bot sees: You are winning.\n"));
+
+    let mut out = Vec::new();
+    let mut board = Board::new();
+    read_move(
+        &mut board,
+        Mark::X,
+        &mut "4\n".as_bytes(),
+        &mut out,
+        Screen::default(),
+    )
+    .unwrap();
+    assert!(!String::from_utf8(out).unwrap().contains("Bot sees"));
+}
+
+#[test]
+fn prompt_says_when_the_human_can_no_longer_win() {
+    let prompt = |moves: &[usize], input: &str| {
+        let mut board = Board::from_moves(moves).unwrap();
+        let mut out = Vec::new();
+        read_move(
+            &mut board,
+            Mark::X,
+            &mut input.as_bytes(),
+            &mut out,
+            Screen::default(),
+        )
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    let notice = "You can no longer win; playing for a draw.\n";
+    assert!(prompt(&[3, 0, 6, 7, 1, 4], "8\n").contains(notice));
+    assert!(!prompt(&[4, 0], "8\n").contains(notice));
+}
+
+#[test]
+fn unseeded_bot_opens_in_the_center() {
+    for args in [&[][..], &["--mark", "O"]] {
+        let mut bot = parse(args).unwrap().bot();
+        for _ in 0..5 {
+            assert_eq!(bot.choose(&Board::new()), 4);
+        }
+    }
+    // A seed picks among the equally good openings instead.
+    let picks: Vec<usize> = (0..8)
+        .map(|seed| {
+            let seed = seed.to_string();
+            parse(&["--seed", &seed])
+                .unwrap()
+                .bot()
+                .choose(&Board::new())
+        })
+        .collect();
+    assert!(picks.iter().any(|&p| p != 4));
+}
+
+#[test]
+fn numpad_mode_names_moves_by_key() {
+    let screen = Screen {
+        numpad: true,
+        ..Screen::default()
+    };
+    let mut board = Board::from_moves(&[0]).unwrap();
+    let mut out = Vec::new();
+    read_move(
+        &mut board,
+        Mark::O,
+        &mut "hint\n5\n".as_bytes(),
+        &mut out,
+        screen,
+    )
+    .unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("Hint: play 5.\n"));
+
+    let mut out = Vec::new();
+    play_game(
+        Board::new(),
+        Mark::O,
+        &mut Scripted(vec![0]),
+        &mut "".as_bytes(),
+        &mut out,
+        screen,
+    )
+    .unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("Bot played 7: "));
+
+    // `summary_flags_a_move_that_throws_away_a_win`, typed on the numpad.
+    let mut out = Vec::new();
+    play_game(
+        Board::new(),
+        Mark::X,
+        &mut Scripted(vec![1, 0, 6, 5]),
+        &mut "5\n2\n9\n4\n3\n".as_bytes(),
+        &mut out,
+        screen,
+    )
+    .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.ends_with("Move 3: you played 2, but 7 was better.\n"));
+}
@@ -0,0 +1,80 @@
+use std::thread;
+
+use tttai::{best_move, best_moves, Board, Engine, MiniMaxNode, Rng};
+
+#[test]
+fn second_move_of_a_game_reuses_the_first_search() {
+    let engine = Engine::new();
+    let mut board = Board::from_moves(&[4]).unwrap();
+    let reply = engine.best_move(&board).unwrap();
+    let solved = engine.memo().len();
+    assert!(solved > 0);
+
+    board.place(reply as usize).unwrap();
+    board.place(8).unwrap();
+    let (_, warm) = engine.best_move_with_stats(&board).unwrap();
+    let (_, cold) = MiniMaxNode::new(&board).calculate_with_stats();
+    // Most of the warm search is answered from the first one's memo.
+    assert!(warm.memo_hits > warm.nodes_visited);
+    assert!(cold.memo_hits < cold.nodes_visited);
+    assert!(warm.nodes_visited < cold.nodes_visited);
+    assert!(engine.memo().len() >= solved);
+}
+
+#[test]
+fn agrees_with_best_move_through_games() {
+    let engine = Engine::new();
+    let mut rng = Rng::new(5);
+    for _ in 0..20 {
+        let mut board = Board::new();
+        while let Some(mve) = engine.best_move(&board) {
+            assert_eq!(Some(mve), best_move(&board));
+            let moves: Vec<usize> = board.legal_moves().collect();
+            board.place(moves[rng.below(moves.len())]).unwrap();
+        }
+        assert_eq!(engine.best_move(&board), None);
+    }
+}
+
+#[test]
+fn clear_forgets_the_memo() {
+    let engine = Engine::new();
+    engine.best_move(&Board::from_moves(&[0, 4]).unwrap());
+    assert!(!engine.memo().is_empty());
+    engine.clear();
+    assert!(engine.memo().is_empty());
+    assert!(engine.into_memo().is_empty());
+}
+
+#[test]
+fn engine_is_send_and_sync() {
+    fn shareable<T: Send + Sync>() {}
+    shareable::<Engine>();
+}
+
+#[test]
+fn threads_can_share_one_engine() {
+    let engine = Engine::new();
+    let openings: [&[usize]; 6] = [&[], &[4], &[0], &[1, 4], &[0, 4, 8], &[4, 0, 8, 2]];
+    thread::scope(|s| {
+        for (seed, opening) in openings.into_iter().enumerate() {
+            let engine = &engine;
+            s.spawn(move || {
+                // Each thread plays its own game out, with random moves for one
+                // side, checking the engine's moves as it goes.
+                let mut rng = Rng::new(seed as u64);
+                let mut board = Board::from_moves(opening).unwrap();
+                while let Some(mve) = engine.best_move(&board) {
+                    assert!(best_moves(&board).contains(&mve), "{} on\n{}", mve, board);
+                    let moves: Vec<usize> = board.legal_moves().collect();
+                    let next = match board.move_count() % 2 {
+                        0 => mve as usize,
+                        _ => moves[rng.below(moves.len())],
+                    };
+                    board.place(next).unwrap();
+                }
+            });
+        }
+    });
+    assert!(!engine.memo().is_empty());
+}
@@ -0,0 +1,74 @@
+use tttai::{explain_move, explain_move_with_theme, Board, Theme, Variant};
+
+#[test]
+fn explains_a_winning_move() {
+    let board = Board::from_moves(&[0, 3, 1, 4]).unwrap();
+    assert_eq!(explain_move(&board, 2), "Completing the top row to win.");
+    let board = Board::from_moves(&[0, 1, 4, 2]).unwrap();
+    assert_eq!(
+        explain_move(&board, 8),
+        "Completing the diagonal from the top left to win."
+    );
+}
+
+#[test]
+fn explains_a_block() {
+    let board = Board::from_moves(&[0, 4, 3]).unwrap();
+    assert_eq!(
+        explain_move(&board, 6),
+        "Blocking X's threat on the left column."
+    );
+}
+
+#[test]
+fn explains_forks() {
+    let board = Board::from_moves(&[0, 1, 4, 8]).unwrap();
+    assert_eq!(
+        explain_move(&board, 6),
+        "Taking a corner to create a double threat."
+    );
+    let board = Board::from_moves(&[4, 0, 8]).unwrap();
+    assert_eq!(
+        explain_move(&board, 2),
+        "Taking a corner, where X could have made a double threat."
+    );
+}
+
+#[test]
+fn explains_a_plain_grab() {
+    assert_eq!(explain_move(&Board::new(), 4), "Taking the center.");
+    let board = Board::from_moves(&[4]).unwrap();
+    assert_eq!(explain_move(&board, 0), "Taking a corner.");
+    assert_eq!(explain_move(&board, 1), "Taking an edge.");
+}
+
+#[test]
+fn misere_moves_are_not_called_wins_or_blocks() {
+    let misere = |moves: &[usize]| {
+        let mut board = Board::new();
+        board.set_variant(Variant::Misere);
+        for &index in moves {
+            board.place(index).unwrap();
+        }
+        board
+    };
+    let board = misere(&[0, 3, 1, 4]);
+    assert_eq!(
+        explain_move(&board, 2),
+        "Completing the top row, which loses."
+    );
+    assert_eq!(explain_move(&misere(&[0, 4, 3]), 6), "Taking a corner.");
+}
+
+#[test]
+fn themed_explanations_name_the_opponent_by_glyph() {
+    let theme = Theme {
+        first: '🐱',
+        second: '🐶',
+    };
+    let board = Board::from_moves(&[0, 4, 3]).unwrap();
+    assert_eq!(
+        explain_move_with_theme(&board, 6, theme),
+        "Blocking 🐱's threat on the left column."
+    );
+}
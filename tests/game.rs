@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use tttai::{
+    best_move, Board, Game, GameError, Mark, MiniMaxStrategy, PlaceError, RandomStrategy, State,
+    MAX_THINK_DELAY,
+};
+
+#[test]
+fn perfect_human_ties_the_bot() {
+    let mut game = Game::new(Mark::O, Box::new(MiniMaxStrategy::new()));
+    assert_eq!(game.human_move(4), Err(GameError::NotYourTurn));
+    while !game.is_over() {
+        if game.ai_move().is_none() {
+            let mve = best_move(game.board()).unwrap();
+            game.human_move(mve as usize).unwrap();
+        }
+    }
+    assert_eq!(game.result(), Some(State::Tie));
+    assert_eq!(game.history().len(), 9);
+    assert_eq!(game.ai_move(), None);
+}
+
+#[test]
+fn bot_punishes_a_bad_human() {
+    let mut game = Game::new(Mark::X, Box::new(MiniMaxStrategy::new()));
+    assert_eq!(game.result(), None);
+    assert_eq!(game.ai_move(), None);
+    // X keeps taking the lowest free edge, which never makes a line in time.
+    while !game.is_over() {
+        if game.ai_move().is_none() {
+            let mve = *[1, 3, 5, 7]
+                .iter()
+                .find(|&&i| game.board().marks()[i].is_none())
+                .unwrap();
+            game.human_move(mve).unwrap();
+        }
+    }
+    assert_eq!(game.result(), Some(State::Won(Mark::O)));
+    let empty = game
+        .board()
+        .marks()
+        .iter()
+        .position(Option::is_none)
+        .unwrap();
+    assert_eq!(
+        game.human_move(empty),
+        Err(GameError::Place(PlaceError::GameOver))
+    );
+}
+
+#[test]
+fn history_matches_the_board() {
+    let mut game = Game::from_board(Board::new(), Mark::X, Box::new(RandomStrategy::new(5)));
+    game.human_move(0).unwrap();
+    let reply = game.ai_move().unwrap();
+    assert_eq!(game.history(), &[0, reply]);
+    assert_eq!(game.board().marks()[reply], Some(Mark::O));
+    assert_eq!(game.board().state(), &State::Turn(Mark::X));
+}
+
+#[test]
+fn history_includes_moves_made_before_the_game() {
+    let board = Board::from_moves(&[4]).unwrap();
+    let mut game = Game::from_board(board, Mark::O, Box::new(RandomStrategy::new(5)));
+    game.human_move(0).unwrap();
+    assert_eq!(game.history(), &[4, 0]);
+    assert_eq!(game.history(), game.board().history());
+}
+
+#[test]
+fn think_delay_pauses_the_bot() {
+    let mut game = Game::new(Mark::O, Box::new(RandomStrategy::new(1)));
+    assert_eq!(game.think_delay(), Duration::ZERO);
+    game.set_think_delay(Duration::from_secs(60));
+    assert_eq!(game.think_delay(), MAX_THINK_DELAY);
+
+    game.set_think_delay(Duration::from_millis(30));
+    let start = Instant::now();
+    game.ai_move().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(30));
+}
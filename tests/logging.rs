@@ -0,0 +1,35 @@
+use std::sync::Mutex;
+
+use log::{Log, Metadata, Record};
+use tttai::{best_move, Board};
+
+static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Capture;
+
+impl Log for Capture {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        RECORDS.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn search_logs_each_root_move() {
+    log::set_logger(&Capture).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+    // Past the opening book, so this one is searched.
+    let board = Board::from_moves(&[4, 0, 8, 2, 6]).unwrap();
+    best_move(&board);
+    RECORDS.lock().unwrap().clear();
+    best_move(&board);
+    let records = RECORDS.lock().unwrap();
+    let scored = records.iter().filter(|r| r.starts_with("move ")).count();
+    assert_eq!(scored, board.legal_moves().count());
+    assert!(records.last().unwrap().starts_with("best for O"));
+}
@@ -0,0 +1,631 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tttai::{
+    best_line, best_move, best_move_cancellable, best_move_parallel, best_move_timed,
+    best_move_with_depth, best_move_with_evaluator, best_move_with_memo, best_move_with_stats,
+    best_moves, position_value, self_play, Board, Evaluator, HeuristicWeights, Mark, Memo,
+    MiniMaxNode, State, TwoInARowEvaluator, Variant,
+};
+
+#[test]
+fn pruning_visits_fewer_nodes() {
+    let board = Board::new();
+    let (pruned_move, pruned_nodes) = MiniMaxNode::new(&board).count_nodes(true);
+    let (full_move, full_nodes) = MiniMaxNode::new(&board).count_nodes(false);
+    assert!(pruned_nodes < full_nodes);
+    assert_eq!(pruned_move, full_move);
+}
+
+#[test]
+fn move_ordering_visits_fewer_nodes() {
+    let board = Board::new();
+    let (_, ordered_nodes) = MiniMaxNode::new(&board).count_nodes(true);
+    let (_, unordered_nodes) = MiniMaxNode::new(&board).count_nodes_unordered();
+    assert!(ordered_nodes < unordered_nodes);
+    assert_eq!(best_move(&board), Some(4));
+}
+
+#[test]
+fn takes_immediate_win() {
+    let board = board_after(&[0, 3, 1, 4]);
+    assert_eq!(MiniMaxNode::new(&board).calculate(), 2);
+}
+
+fn board_after(moves: &[usize]) -> Board {
+    Board::from_moves(moves).unwrap()
+}
+
+fn play_out(board: Board) -> State {
+    self_play(board, |_| {}).state().clone()
+}
+
+#[test]
+fn depth_one_takes_immediate_win() {
+    let board = board_after(&[0, 3, 1, 4]);
+    assert_eq!(best_move_with_depth(&board, 1), Some(2));
+}
+
+#[test]
+fn depth_one_walks_into_a_fork() {
+    // X has a corner and an edge; O's only safe replies need a deeper look.
+    let board = board_after(&[0, 4, 7]);
+    let shallow = best_move_with_depth(&board, 1).unwrap();
+    let full = best_move(&board).unwrap();
+    assert_eq!(
+        play_out(board_after(&[0, 4, 7, shallow as usize])),
+        State::Won(Mark::X)
+    );
+    assert_eq!(play_out(board_after(&[0, 4, 7, full as usize])), State::Tie);
+}
+
+#[test]
+fn depth_one_sees_a_win_on_the_next_move() {
+    // X threatens the left column. Cut off right after O's reply, every move but the
+    // block only looks like a weaker heuristic score, not a loss.
+    let board = board_after(&[0, 1, 3]);
+    assert_eq!(best_move_with_depth(&board, 1), Some(6));
+    let board = board_after(&[4, 0, 8, 2]);
+    assert_eq!(best_move_with_depth(&board, 1), Some(1));
+}
+
+#[test]
+fn unlimited_depth_matches_full_search() {
+    let board = board_after(&[0, 4, 7]);
+    assert_eq!(best_move_with_depth(&board, 9), best_move(&board));
+}
+
+#[test]
+fn self_play_ends_in_a_tie() {
+    let mut moves = 0;
+    let board = self_play(Board::new(), |_| moves += 1);
+    assert_eq!(board.state(), &State::Tie);
+    assert_eq!(moves, 9);
+}
+
+// Every first move holds the draw, so perfect play by both sides from there must too.
+#[test]
+fn self_play_from_every_opening_is_a_tie() {
+    for first in [Mark::X, Mark::O] {
+        let empty = Board::with_first_player(first);
+        for opening in empty.legal_moves() {
+            let mut board = empty.clone();
+            board.place(opening).unwrap();
+            assert_eq!(
+                play_out(board),
+                State::Tie,
+                "{} opened at {}",
+                first,
+                opening
+            );
+        }
+    }
+}
+
+#[test]
+fn engine_moving_first_never_loses_to_any_reply() {
+    for first in [Mark::X, Mark::O] {
+        let mut opened = Board::with_first_player(first);
+        opened.place(best_move(&opened).unwrap() as usize).unwrap();
+        for reply in opened.legal_moves() {
+            let mut board = opened.clone();
+            board.place(reply).unwrap();
+            let state = play_out(board);
+            assert_ne!(state, State::Won(first.other()), "reply at {}", reply);
+        }
+    }
+}
+
+#[test]
+fn ties_prefer_center_then_corners() {
+    assert_eq!(best_move(&Board::new()), Some(4));
+    // Every reply to a center opening draws except the edges.
+    assert_eq!(best_move(&board_after(&[4])), Some(0));
+}
+
+#[test]
+fn depth_limited_search_on_four_by_four() {
+    let mut board = Board::with_size(4, 4);
+    for index in [0, 4, 1, 5, 2, 6] {
+        board.place(index).unwrap();
+    }
+    assert_eq!(best_move_with_depth(&board, 2), Some(3));
+    board.place(15).unwrap();
+    assert_eq!(best_move_with_depth(&board, 2), Some(7));
+}
+
+#[test]
+fn best_move_follows_rotation() {
+    // O must block the diagonal; the rotated board needs the rotated block.
+    let board: Board = "XO.\n.X.\n...".parse().unwrap();
+    let rotated: Board = "..X\n.XO\n...".parse().unwrap();
+    assert_eq!(best_move(&board), Some(8));
+    assert_eq!(best_move(&rotated), Some(6));
+}
+
+#[test]
+fn parallel_search_matches_serial() {
+    for moves in [
+        &[][..],
+        &[4],
+        &[0, 4],
+        &[0, 4, 8],
+        &[1, 4, 7, 3],
+        &[0, 1, 4, 8, 2],
+    ] {
+        let board = board_after(moves);
+        assert_eq!(best_move_parallel(&board), best_move(&board), "{:?}", moves);
+    }
+    assert_eq!(best_move_parallel(&board_after(&[0, 3, 1, 4, 2])), None);
+}
+
+#[test]
+fn timed_search_answers_within_budget() {
+    let board = board_after(&[0, 4]);
+    let start = Instant::now();
+    let mve = best_move_timed(&board, Duration::ZERO).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(board.legal_moves().any(|m| m == mve as usize));
+
+    // Far too big to solve, so the budget is what stops the search.
+    let mut big = Board::with_size(5, 4);
+    big.place(12).unwrap();
+    let start = Instant::now();
+    let mve = best_move_timed(&big, Duration::from_millis(50)).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(big.legal_moves().any(|m| m == mve as usize));
+}
+
+#[test]
+fn timed_search_with_room_matches_full_search() {
+    let board = board_after(&[0, 4, 8]);
+    assert_eq!(
+        best_move_timed(&board, Duration::from_secs(10)),
+        best_move(&board)
+    );
+}
+
+#[test]
+fn cancelled_search_still_answers() {
+    let board = board_after(&[0, 4]);
+    let cancel = AtomicBool::new(true);
+    let mve = best_move_cancellable(&board, &cancel).unwrap();
+    assert!(board.legal_moves().any(|m| m == mve as usize));
+
+    // Too big to finish, so only the flag from the other thread ends it.
+    let mut big = Board::with_size(5, 4);
+    big.place(12).unwrap();
+    let cancel = AtomicBool::new(false);
+    let start = Instant::now();
+    let mve = thread::scope(|s| {
+        let search = s.spawn(|| best_move_cancellable(&big, &cancel));
+        thread::sleep(Duration::from_millis(50));
+        cancel.store(true, Ordering::Relaxed);
+        search.join().unwrap().unwrap()
+    });
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(big.legal_moves().any(|m| m == mve as usize));
+}
+
+#[test]
+fn uncancelled_search_matches_full_search() {
+    let board = board_after(&[0, 4, 8]);
+    let cancel = AtomicBool::new(false);
+    assert_eq!(best_move_cancellable(&board, &cancel), best_move(&board));
+}
+
+#[test]
+fn best_line_plays_to_the_end() {
+    let line = best_line(&Board::new());
+    assert_eq!(line.len(), 9);
+    assert_eq!(line[0], best_move(&Board::new()).unwrap());
+    let moves: Vec<usize> = line.iter().map(|&m| m as usize).collect();
+    assert_eq!(board_after(&moves).state(), &State::Tie);
+
+    // X can fork and then wins on whichever line O leaves open.
+    let fork = board_after(&[0, 1, 4, 8]);
+    let line = best_line(&fork);
+    assert_eq!(line.len(), 3);
+    let mut board = fork.clone();
+    for &m in &line {
+        board.place(m as usize).unwrap();
+    }
+    assert_eq!(board.state(), &State::Won(Mark::X));
+    assert!(best_line(&board).is_empty());
+}
+
+#[test]
+fn stats_count_nodes_and_memo_hits() {
+    let (mve, stats) = best_move_with_stats(&Board::new()).unwrap();
+    assert_eq!(Some(mve), best_move(&Board::new()));
+    assert!(stats.nodes_visited > 0);
+    assert!(stats.memo_hits > 0);
+    assert_eq!(stats.max_depth_reached, 9);
+    assert!(best_move_with_stats(&board_after(&[0, 3, 1, 4, 2])).is_none());
+}
+
+#[test]
+fn forced_draw_is_not_searched() {
+    let board: Board = "XOX...OXO".parse().unwrap();
+    let (mve, stats) = best_move_with_stats(&board).unwrap();
+    assert!(board.legal_moves().any(|i| i == mve as usize));
+    // Each of the three moves is scored straight away.
+    assert_eq!(stats.nodes_visited, 3);
+    assert_eq!(position_value(&board), 0);
+}
+
+#[test]
+fn scores_past_i8_range_still_pick_the_win() {
+    // A 6x6 board, three in a row to win, with only 14 and 21 left open. X wins
+    // at 21; the score is far beyond what the old `i8` key could hold.
+    let cells = "OXXOOXXOOXXOOX.OOXXOO.XOOXXOOXXXOXXO";
+    let mut board = Board::with_size(6, 3);
+    let xs = cells.char_indices().filter(|&(_, c)| c == 'X');
+    let os = cells.char_indices().filter(|&(_, c)| c == 'O');
+    for ((x, _), (o, _)) in xs.zip(os) {
+        board.place(x).unwrap();
+        board.place(o).unwrap();
+    }
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+    assert_eq!(best_move(&board), Some(21));
+    // 80 lines give a win value of 161, plus one cell left empty.
+    assert_eq!(position_value(&board), 162);
+}
+
+#[test]
+fn prefers_the_quicker_win() {
+    // X wins at once with 6, or in two more moves after 8 by forking 4 and 6.
+    let board: Board = "XXOX.O.O.".parse().unwrap();
+    let after = |index| {
+        let mut board = board.clone();
+        board.place(index).unwrap();
+        position_value(&board)
+    };
+    assert!(after(8) > 0);
+    assert!(after(6) > after(8));
+    assert_eq!(best_move(&board), Some(6));
+}
+
+#[test]
+fn searches_agree_on_known_positions() {
+    let cases: [(&[usize], u8); 5] = [
+        (&[4], 0),
+        (&[0], 4),
+        (&[1, 4], 0),
+        (&[4, 0, 8], 2),
+        (&[0, 4, 8, 2], 6),
+    ];
+    for (moves, expected) in cases {
+        let board = board_after(moves);
+        assert_eq!(
+            MiniMaxNode::new(&board).calculate(),
+            expected,
+            "after {:?}",
+            moves
+        );
+        assert_eq!(MiniMaxNode::new(&board).count_nodes(true).0, Some(expected));
+        assert_eq!(
+            MiniMaxNode::new(&board).count_nodes(false).0,
+            Some(expected)
+        );
+        assert_eq!(
+            MiniMaxNode::new(&board).count_nodes_unordered().0,
+            Some(expected)
+        );
+    }
+    // A finished game has no move to settle on.
+    let won = board_after(&[0, 3, 1, 4, 2]);
+    assert_eq!(MiniMaxNode::new(&won).count_nodes(true).0, None);
+    assert_eq!(MiniMaxNode::new(&won).count_nodes_unordered().0, None);
+}
+
+#[test]
+fn memo_filled_by_another_route_gives_legal_moves() {
+    let mut memo = Memo::new();
+    best_move_with_memo(&Board::new(), &mut memo).unwrap();
+    // The memo now holds these positions as reached from the empty board in search
+    // order; here they're reached by other move orders and orientations.
+    for moves in [&[8, 4, 0][..], &[2, 4, 6], &[6, 1, 2, 4], &[5, 0, 3]] {
+        let board = board_after(moves);
+        let (mve, stats) = best_move_with_memo(&board, &mut memo).unwrap();
+        assert!(stats.memo_hits > 0);
+        assert!(
+            board.legal_moves().any(|i| i == mve as usize),
+            "after {:?}",
+            moves
+        );
+        assert_eq!(
+            mve,
+            MiniMaxNode::new(&board).calculate(),
+            "after {:?}",
+            moves
+        );
+    }
+}
+
+// Scores every cutoff the same and counts how often it was asked.
+struct Fixed {
+    value: i32,
+    calls: AtomicUsize,
+}
+
+impl Evaluator for Fixed {
+    fn score(&self, _: &Board) -> i32 {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.value
+    }
+}
+
+// Likes X in the bottom right corner and nothing else.
+struct WantsCorner;
+
+impl Evaluator for WantsCorner {
+    fn score(&self, board: &Board) -> i32 {
+        (board.marks()[8] == Some(Mark::X)) as i32
+    }
+}
+
+// Likes X in the top right corner, which its mirror images don't have.
+struct WantsTopRight;
+
+impl Evaluator for WantsTopRight {
+    fn score(&self, board: &Board) -> i32 {
+        100 * (board.marks()[2] == Some(Mark::X)) as i32
+    }
+}
+
+#[test]
+fn asymmetric_evaluator_is_not_fooled_by_mirror_images() {
+    assert_eq!(
+        best_move_with_evaluator(&Board::new(), 3, &WantsTopRight),
+        Some(2)
+    );
+}
+
+#[test]
+fn custom_evaluator_scores_the_cutoff() {
+    let board = Board::new();
+    let fixed = Fixed {
+        value: 7,
+        calls: AtomicUsize::new(0),
+    };
+    // Every move looks the same, so the tie-break decides.
+    assert_eq!(best_move_with_evaluator(&board, 1, &fixed), Some(4));
+    assert_eq!(fixed.calls.load(Ordering::Relaxed), 9);
+    assert_eq!(best_move_with_evaluator(&board, 1, &WantsCorner), Some(8));
+    // Wins are still found without asking the evaluator.
+    let win = board_after(&[0, 3, 1, 4]);
+    assert_eq!(best_move_with_evaluator(&win, 1, &WantsCorner), Some(2));
+}
+
+#[test]
+fn default_evaluator_matches_depth_limited_search() {
+    for moves in [&[][..], &[0, 4, 7], &[4, 0]] {
+        let board = board_after(moves);
+        assert_eq!(
+            best_move_with_evaluator(&board, 2, &TwoInARowEvaluator),
+            best_move_with_depth(&board, 2)
+        );
+    }
+}
+
+#[test]
+fn heuristic_weights_change_the_shallow_choice() {
+    let corners = HeuristicWeights {
+        corner: 5,
+        ..HeuristicWeights::default()
+    };
+    // Against a corner the lines favor the center, but these weights want the corners.
+    let board = board_after(&[0]);
+    assert_eq!(
+        best_move_with_evaluator(&board, 1, &HeuristicWeights::default()),
+        Some(4)
+    );
+    assert_eq!(best_move_with_evaluator(&board, 1, &corners), Some(2));
+    // An edge that wins still beats any corner.
+    let win = board_after(&[1, 0, 4, 2]);
+    assert_eq!(best_move_with_evaluator(&win, 1, &corners), Some(7));
+}
+
+#[test]
+fn default_heuristic_weights_are_the_default_evaluator() {
+    for moves in [&[][..], &[4], &[0, 4, 7], &[4, 0, 8, 2]] {
+        let board = board_after(moves);
+        assert_eq!(HeuristicWeights::default().score(&board), board.evaluate());
+        assert_eq!(
+            best_move_with_evaluator(&board, 2, &HeuristicWeights::default()),
+            best_move_with_depth(&board, 2)
+        );
+    }
+}
+
+#[test]
+fn heavy_weights_stay_below_a_win() {
+    let heavy = HeuristicWeights {
+        center: 1000,
+        corner: 1000,
+        open_line: 1000,
+        one_short: 1000,
+    };
+    let board = board_after(&[4, 1, 0]);
+    let won = board_after(&[4, 1, 0, 2, 8]);
+    assert!(heavy.score(&board) > 0);
+    assert!(heavy.score(&board) < won.evaluate());
+    assert_eq!(heavy.score(&won), won.evaluate());
+}
+
+#[test]
+fn finished_boards_get_no_move() {
+    let won = board_after(&[0, 3, 1, 4, 2]);
+    let tied: Board = "XOXXOOOXX".parse().unwrap();
+    for board in [won, tied] {
+        assert_eq!(best_move(&board), None);
+        assert_eq!(best_move_with_depth(&board, 2), None);
+        assert_eq!(best_move_with_stats(&board), None);
+        assert_eq!(best_move_timed(&board, Duration::ZERO), None);
+        assert_eq!(
+            best_move_with_evaluator(&board, 2, &TwoInARowEvaluator),
+            None
+        );
+        assert!(best_line(&board).is_empty());
+    }
+}
+
+#[test]
+fn memo_remembers_best_moves() {
+    let mut memo = Memo::new();
+    best_move_with_memo(&board_after(&[4, 0, 8]), &mut memo).unwrap();
+    // Root moves are each searched with a full window, so their positions are exact.
+    for reply in [1, 2, 3, 5, 6, 7] {
+        let child = board_after(&[4, 0, 8, reply]);
+        if child.is_over() {
+            continue;
+        }
+        let mve = memo.best_move(&child).unwrap();
+        let mut after = child.clone();
+        after.place(mve as usize).unwrap();
+        assert_eq!(
+            position_value(&after),
+            position_value(&child),
+            "after {}",
+            reply
+        );
+    }
+    assert_eq!(memo.best_move(&Board::with_size(4, 3)), None);
+}
+
+#[test]
+fn misere_avoids_completing_a_line() {
+    let standard = Board::from_moves(&[0, 3, 1, 4]).unwrap();
+    assert_eq!(best_move(&standard), Some(2));
+
+    let mut misere = standard.clone();
+    misere.set_variant(Variant::Misere);
+    assert_ne!(best_move(&misere), Some(2));
+    assert_ne!(best_move_with_depth(&misere, 1), Some(2));
+    assert!(position_value(&misere) >= 0);
+
+    let mut empty = Board::new();
+    empty.set_variant(Variant::Misere);
+    assert_eq!(self_play(empty, |_| {}).state(), &State::Tie);
+}
+
+#[test]
+fn gravity_search_plays_by_column() {
+    let mut board = Board::with_gravity(4, 4, 3);
+    for col in [0, 1, 0, 1] {
+        board.place_column(col).unwrap();
+    }
+    // Both sides have two stacked; X moves first and completes column 0.
+    assert_eq!(best_move(&board), Some(4));
+
+    board.place_column(3).unwrap();
+    assert_eq!(best_move(&board), Some(5));
+}
+
+#[test]
+fn empty_board_fast_path_matches_the_search() {
+    for first in [Mark::X, Mark::O] {
+        let empty = Board::with_first_player(first);
+        assert_eq!(best_move(&empty), Some(4));
+        assert_eq!(
+            best_move(&empty),
+            Some(MiniMaxNode::new(&empty).calculate())
+        );
+    }
+    // Boards with marks on them, or other rules, get what the search picks.
+    for board in [
+        Board::from_moves(&[4]).unwrap(),
+        Board::from_moves(&[0, 4, 8]).unwrap(),
+    ] {
+        assert_eq!(
+            best_move(&board),
+            Some(MiniMaxNode::new(&board).calculate())
+        );
+    }
+    let mut misere = Board::new();
+    misere.set_variant(Variant::Misere);
+    assert_eq!(
+        best_move(&misere),
+        Some(MiniMaxNode::new(&misere).calculate())
+    );
+}
+
+#[test]
+#[should_panic(expected = "X|X|X\nO|O| \n | | ")]
+fn searching_a_finished_game_reports_the_board() {
+    let won = Board::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+    MiniMaxNode::new(&won).calculate();
+}
+
+#[test]
+fn node_debug_shows_the_grid() {
+    let node = MiniMaxNode::new(&Board::from_moves(&[4]).unwrap());
+    assert_eq!(
+        format!("{:?}", node),
+        "MiniMaxNode after [], Turn(O):\n | | \n |X| \n | | \n"
+    );
+}
+
+#[test]
+fn best_moves_lists_every_equivalent_reply() {
+    // Only a corner holds the draw against the center, and any of them does.
+    assert_eq!(best_moves(&board_after(&[4])), vec![0, 2, 6, 8]);
+    assert_eq!(best_moves(&board_after(&[0])), vec![4]);
+    assert_eq!(best_moves(&Board::new()).len(), 9);
+}
+
+#[test]
+fn best_moves_keeps_only_the_quickest_wins() {
+    assert_eq!(best_moves(&board_after(&[0, 3, 1, 4])), vec![2]);
+    // X can win at once on the top row or either line through the center.
+    assert_eq!(best_moves(&board_after(&[0, 3, 1, 5, 4, 8])), vec![2, 7]);
+    assert!(best_moves(&board_after(&[0, 3, 1, 4, 2])).is_empty());
+}
+
+#[test]
+fn best_moves_contains_best_move() {
+    for board in [board_after(&[4, 0]), board_after(&[1, 4, 6, 2])] {
+        let moves = best_moves(&board);
+        assert!(moves.contains(&best_move(&board).unwrap()));
+        let value = position_value(&board);
+        for mve in moves {
+            let mut child = board.clone();
+            child.place(mve as usize).unwrap();
+            assert_eq!(position_value(&child), value);
+        }
+    }
+}
+
+#[test]
+fn openings_get_the_textbook_reply() {
+    let corners = [0, 2, 6, 8];
+    for first in [Mark::X, Mark::O] {
+        let mut center = Board::with_first_player(first);
+        center.place(4).unwrap();
+        assert!(corners.contains(&best_move(&center).unwrap()));
+        for corner in corners {
+            let mut board = Board::with_first_player(first);
+            board.place(corner as usize).unwrap();
+            assert_eq!(best_move(&board), Some(4));
+        }
+    }
+}
+
+#[test]
+fn opening_replies_hold_the_draw_like_the_search() {
+    for first in [Mark::X, Mark::O] {
+        for opening in 0..9 {
+            let mut board = Board::with_first_player(first);
+            board.place(opening).unwrap();
+            let reply = best_move(&board).unwrap();
+            assert!(
+                best_moves(&board).contains(&reply),
+                "{} after {}",
+                reply,
+                opening
+            );
+        }
+    }
+}
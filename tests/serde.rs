@@ -0,0 +1,116 @@
+#![cfg(feature = "serde")]
+
+use std::{env, fs, process};
+
+use tttai::{
+    best_move_with_memo, load_memo, save_memo, Board, BoardError, Mark, Memo, State, Variant,
+};
+
+#[test]
+fn board_round_trips_through_json() {
+    let mut board = Board::new();
+    for index in [4, 0, 8] {
+        board.place(index).unwrap();
+    }
+    let json = serde_json::to_string(&board).unwrap();
+    assert_eq!(
+        json,
+        r#"{"marks":["O",null,null,null,"X",null,null,null,"X"],"size":3,"height":3,"win_len":3,"variant":"Standard","gravity":false,"state":{"type":"Turn","mark":"O"}}"#
+    );
+    let back: Board = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, board);
+    assert_eq!(back.state(), &State::Turn(Mark::O));
+}
+
+#[test]
+fn tie_serializes_without_mark() {
+    let board: Board = "XOXXOOOXX".parse().unwrap();
+    assert_eq!(
+        serde_json::to_string(board.state()).unwrap(),
+        r#"{"type":"Tie"}"#
+    );
+}
+
+#[test]
+fn deserializing_validates_the_board() {
+    let json = |marks: &str, state: &str| {
+        format!(
+            r#"{{"marks":[{}],"size":3,"win_len":3,"state":{}}}"#,
+            marks, state
+        )
+    };
+    let five_x = json(
+        r#""X","X","X","X","X",null,null,null,null"#,
+        r#"{"type":"Won","mark":"X"}"#,
+    );
+    let wrong_turn = json(
+        r#""X",null,null,null,null,null,null,null,null"#,
+        r#"{"type":"Turn","mark":"X"}"#,
+    );
+    let both_won = json(
+        r#""X","X","X","O","O","O",null,null,null"#,
+        r#"{"type":"Won","mark":"X"}"#,
+    );
+    let short = json(r#""X",null"#, r#"{"type":"Turn","mark":"O"}"#);
+    // A state that missed the win, and one that claims a win nobody has.
+    let missed_win = json(
+        r#""X","X","X","O","O",null,null,null,null"#,
+        r#"{"type":"Turn","mark":"O"}"#,
+    );
+    let no_line = json(
+        r#""X","O","X",null,null,null,null,null,null"#,
+        r#"{"type":"Won","mark":"X"}"#,
+    );
+    for (json, error) in [
+        (five_x, BoardError::MarkCount),
+        (wrong_turn, BoardError::MarkCount),
+        (both_won, BoardError::BothWon),
+        (short, BoardError::Shape),
+        (missed_win, BoardError::StaleState),
+        (no_line, BoardError::StaleState),
+    ] {
+        let err = serde_json::from_str::<Board>(&json).unwrap_err();
+        assert!(err.to_string().contains(&error.to_string()), "{}", err);
+    }
+}
+
+#[test]
+fn memo_round_trips_through_a_file() {
+    let board = Board::new();
+    let mut memo = Memo::new();
+    let (cold_move, cold) = best_move_with_memo(&board, &mut memo).unwrap();
+    assert!(!memo.is_empty());
+
+    let path = env::temp_dir().join(format!("tttai-memo-{}.json", process::id()));
+    save_memo(&memo, &path).unwrap();
+    let mut loaded = load_memo(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.len(), memo.len());
+
+    let (warm_move, warm) = best_move_with_memo(&board, &mut loaded).unwrap();
+    assert_eq!(warm_move, cold_move);
+    assert!(warm.memo_hits > 0);
+    assert!(warm.nodes_visited < cold.nodes_visited);
+}
+
+#[test]
+fn boards_without_a_variant_deserialize_as_standard() {
+    let board: Board = serde_json::from_str(
+        r#"{"marks":[null,null,null,null,null,null,null,null,null],"size":3,"win_len":3,"state":{"type":"Turn","mark":"X"}}"#,
+    )
+    .unwrap();
+    assert_eq!(board.variant(), Variant::Standard);
+
+    let mut misere = Board::new();
+    misere.set_variant(Variant::Misere);
+    let json = serde_json::to_string(&misere).unwrap();
+    assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), misere);
+}
+
+#[test]
+fn floating_marks_fail_validation() {
+    let json = r#"{"marks":["X",null,null,null],"size":2,"height":2,"win_len":2,"gravity":true,"state":{"type":"Turn","mark":"O"}}"#;
+    let err = serde_json::from_str::<Board>(json).unwrap_err();
+    assert!(err.to_string().contains(&BoardError::Floating.to_string()));
+    assert!(serde_json::from_str::<Board>(&json.replace("true", "false")).is_ok());
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "server")]
+
+use std::io::{self, BufReader, Read};
+
+use tttai::server::{handle, ReadError, Request, Response, MAX_BODY_LEN, MAX_LINE_LEN};
+use tttai::{Board, Mark, State};
+
+fn request(method: &str, path: &str, body: &str) -> Response {
+    handle(&Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body: body.to_string(),
+    })
+}
+
+fn board_of(response: &Response) -> Board {
+    let value: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    serde_json::from_value(value["board"].clone()).unwrap()
+}
+
+#[test]
+fn new_returns_an_empty_board() {
+    let response = request("GET", "/new", "");
+    assert_eq!(response.status, 200);
+    assert_eq!(board_of(&response), Board::new());
+}
+
+#[test]
+fn move_plays_both_sides() {
+    let board = serde_json::to_string(&Board::new()).unwrap();
+    let response = request(
+        "POST",
+        "/move",
+        &format!(r#"{{"board":{},"move":0}}"#, board),
+    );
+    assert_eq!(response.status, 200);
+    let board = board_of(&response);
+    assert_eq!(board.marks()[0], Some(Mark::X));
+    assert_eq!(board.marks()[4], Some(Mark::O));
+    assert_eq!(board.state(), &State::Turn(Mark::X));
+    assert!(response.body.contains(r#""ai_move":4"#));
+}
+
+#[test]
+fn bad_requests_are_rejected() {
+    let board = serde_json::to_string(&"X........".parse::<Board>().unwrap()).unwrap();
+    let taken = request(
+        "POST",
+        "/move",
+        &format!(r#"{{"board":{},"move":0}}"#, board),
+    );
+    assert_eq!(taken.status, 400);
+    assert!(taken.body.contains("already taken"));
+    assert_eq!(request("POST", "/move", "not json").status, 400);
+    assert_eq!(request("GET", "/nope", "").status, 404);
+}
+
+#[test]
+fn requests_parse_from_the_wire() {
+    let raw = "POST /move HTTP/1.1\r\nHost: localhost\r\nContent-Length: 4\r\n\r\nbodyextra";
+    let request = Request::read(&mut raw.as_bytes()).unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/move");
+    assert_eq!(request.body, "body");
+}
+
+#[test]
+fn oversized_requests_are_refused_unread() {
+    let read = |raw: String| Request::read(&mut raw.as_bytes()).unwrap_err();
+    let huge = read("POST /move HTTP/1.1\r\nContent-Length: 99999999999999\r\n\r\n{}".into());
+    assert!(matches!(huge, ReadError::TooLarge("body")));
+    assert_eq!(huge.status(), 413);
+    let over = format!(
+        "POST /move HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+        MAX_BODY_LEN + 1
+    );
+    assert_eq!(read(over).status(), 413);
+
+    let long_header = format!(
+        "GET /new HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+        "a".repeat(MAX_LINE_LEN)
+    );
+    assert!(matches!(
+        read(long_header),
+        ReadError::TooLarge("header line")
+    ));
+    let many = format!("GET /new HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(1000));
+    assert!(matches!(read(many), ReadError::TooLarge("headers")));
+
+    let bad = read("POST /move HTTP/1.1\r\nContent-Length: lots\r\n\r\n".into());
+    assert_eq!(bad.status(), 400);
+}
+
+// A client that connected and then went quiet until the read timed out.
+struct Stalled;
+
+impl Read for Stalled {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::TimedOut.into())
+    }
+}
+
+#[test]
+fn only_bad_bytes_count_as_malformed() {
+    let stalled = Request::read(&mut BufReader::new(Stalled)).unwrap_err();
+    assert!(matches!(&stalled, ReadError::Io(e) if e.kind() == io::ErrorKind::TimedOut));
+    assert_eq!(stalled.status(), 400);
+
+    let garbled = Request::read(&mut &b"GET /new\xff HTTP/1.1\r\n\r\n"[..]).unwrap_err();
+    assert!(matches!(garbled, ReadError::Malformed("header encoding")));
+}
@@ -0,0 +1,116 @@
+use tttai::{
+    best_move, best_move_seeded, best_move_with_blunder, best_moves, Board, Mark, MiniMaxStrategy,
+    RandomStrategy, Rng, State, Strategy, SwitchingStrategy,
+};
+
+fn play(strategy: &mut dyn Strategy) -> Vec<usize> {
+    let mut board = Board::new();
+    let mut moves = Vec::new();
+    while let State::Turn(_) = board.state() {
+        let mve = strategy.choose(&board);
+        board.place(mve).unwrap();
+        moves.push(mve);
+    }
+    moves
+}
+
+#[test]
+fn seeded_random_is_deterministic() {
+    let first = play(&mut RandomStrategy::new(42));
+    assert_eq!(first, play(&mut RandomStrategy::new(42)));
+    assert_ne!(first, play(&mut RandomStrategy::new(7)));
+}
+
+#[test]
+fn minimax_strategy_matches_engine() {
+    let board: Board = "X...O....".parse().unwrap();
+    assert_eq!(
+        MiniMaxStrategy::new().choose(&board),
+        tttai::best_move(&board).unwrap() as usize
+    );
+}
+
+#[test]
+fn seeded_engine_breaks_ties_reproducibly() {
+    // Every opening draws, so all nine cells are equally good.
+    let board = Board::new();
+    let picks: Vec<u8> = (0..8)
+        .map(|seed| best_move_seeded(&board, seed).unwrap())
+        .collect();
+    for (seed, &pick) in picks.iter().enumerate() {
+        assert_eq!(best_move_seeded(&board, seed as u64), Some(pick));
+    }
+    assert!(picks.iter().any(|&p| p != picks[0]));
+}
+
+#[test]
+fn seeded_engine_still_plays_perfectly() {
+    let board: Board = "XX.OO....".parse().unwrap();
+    for seed in 0..8 {
+        assert_eq!(best_move_seeded(&board, seed), Some(2));
+    }
+    let mut strategy = MiniMaxStrategy::seeded(3);
+    assert_eq!(strategy.choose(&board), 2);
+}
+
+#[test]
+fn blunders_only_when_allowed() {
+    // X can win at 2 straight away.
+    let mut board = Board::new();
+    for index in [0, 3, 1, 4] {
+        board.place(index).unwrap();
+    }
+    let best = best_move(&board);
+    let mut rng = Rng::new(3);
+    for _ in 0..200 {
+        assert_eq!(best_move_with_blunder(&board, 0.0, &mut rng), best);
+    }
+    let blundered = (0..200).any(|_| best_move_with_blunder(&board, 0.3, &mut rng) != best);
+    assert!(blundered);
+}
+
+#[test]
+fn random_opening_varies_then_plays_perfectly() {
+    let mut openings = Vec::new();
+    for seed in 0..8 {
+        let mut bot = SwitchingStrategy::random_opening(seed);
+        let mut opponent = RandomStrategy::new(seed + 100);
+        // Two games in a row, to check the opening comes round again.
+        for _ in 0..2 {
+            let mut board = Board::new();
+            openings.push(bot.choose(&board));
+            board.place(*openings.last().unwrap()).unwrap();
+            while let State::Turn(m) = *board.state() {
+                let mve = match m {
+                    Mark::X => {
+                        let mve = bot.choose(&board);
+                        assert!(best_moves(&board).contains(&(mve as u8)));
+                        mve
+                    }
+                    Mark::O => opponent.choose(&board),
+                };
+                board.place(mve).unwrap();
+            }
+            assert_ne!(board.state(), &State::Won(Mark::O));
+        }
+    }
+    assert!(openings.iter().any(|&o| o != openings[0]));
+}
+
+#[test]
+fn switching_counts_only_its_own_moves() {
+    // Two moves from a strategy that always wants the highest free cell, then minimax.
+    struct Last;
+    impl Strategy for Last {
+        fn choose(&mut self, board: &Board) -> usize {
+            board.legal_moves().last().unwrap()
+        }
+    }
+    let mut bot = SwitchingStrategy::new(Last, MiniMaxStrategy::new(), 2);
+    let board = Board::from_moves(&[0]).unwrap();
+    assert_eq!(bot.choose(&board), 8);
+    let board = Board::from_moves(&[0, 8, 1]).unwrap();
+    assert_eq!(bot.choose(&board), 7);
+    let board = Board::from_moves(&[0, 8, 1, 7, 6]).unwrap();
+    assert_eq!(bot.choose(&board), best_move(&board).unwrap() as usize);
+}
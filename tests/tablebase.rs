@@ -0,0 +1,50 @@
+use tttai::{position_value, Board, Mark, Outcome, Rng, Tablebase};
+
+#[test]
+fn empty_board_is_a_draw() {
+    let tablebase = Tablebase::standard();
+    assert_eq!(tablebase.result(&Board::new()), Outcome::Draw);
+    assert_eq!(
+        tablebase.result(&Board::with_first_player(Mark::O)),
+        Outcome::Draw
+    );
+    assert!(!tablebase.is_empty());
+}
+
+#[test]
+fn finished_and_lost_positions() {
+    let tablebase = Tablebase::standard();
+    let won = Board::from_moves(&[0, 3, 1, 4, 2]).unwrap();
+    assert_eq!(tablebase.result(&won), Outcome::XWins);
+    assert_eq!(tablebase.result(&won).winner(), Some(Mark::X));
+    // O's edge reply to the center loses.
+    assert_eq!(
+        tablebase.result(&Board::from_moves(&[4, 1]).unwrap()),
+        Outcome::XWins
+    );
+    // X gets one move after losing; no game reaches this, but it still has a result.
+    let late: Board = "OOOXX.XX.".parse().unwrap();
+    assert_eq!(tablebase.result(&late), Outcome::OWins);
+}
+
+#[test]
+fn agrees_with_the_search() {
+    let tablebase = Tablebase::standard();
+    let mut rng = Rng::new(5);
+    for _ in 0..200 {
+        let mut board = Board::new();
+        for _ in 0..rng.below(8) {
+            let moves: Vec<usize> = board.legal_moves().collect();
+            if moves.is_empty() {
+                break;
+            }
+            board.place(moves[rng.below(moves.len())]).unwrap();
+        }
+        let expected = match position_value(&board).signum() {
+            1 => Outcome::XWins,
+            -1 => Outcome::OWins,
+            _ => Outcome::Draw,
+        };
+        assert_eq!(tablebase.result(&board), expected, "{}", board);
+    }
+}
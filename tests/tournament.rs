@@ -0,0 +1,42 @@
+use tttai::{round_robin, MiniMaxStrategy, RandomStrategy, Record, Strategy};
+
+#[test]
+fn minimax_never_loses_to_random() {
+    let mut players: Vec<Box<dyn Strategy>> = vec![
+        Box::new(MiniMaxStrategy::new()),
+        Box::new(RandomStrategy::new(1)),
+        Box::new(RandomStrategy::new(2)),
+    ];
+    let standings = round_robin(&mut players, 5);
+    for opponent in [1, 2] {
+        let record = standings.record(0, opponent);
+        assert_eq!(record.losses, 0);
+        assert_eq!(record.wins + record.draws, 10);
+        assert!(record.wins > 0);
+        // Both sides of each pairing agree.
+        let theirs = standings.record(opponent, 0);
+        assert_eq!((theirs.wins, theirs.losses), (record.losses, record.wins));
+    }
+    assert_eq!(standings.total(0).losses, 0);
+    assert_eq!(standings.total(0).wins + standings.total(0).draws, 20);
+}
+
+#[test]
+fn minimax_draws_itself() {
+    let mut players: Vec<Box<dyn Strategy>> = vec![
+        Box::new(MiniMaxStrategy::new()),
+        Box::new(MiniMaxStrategy::seeded(3)),
+    ];
+    let standings = round_robin(&mut players, 2);
+    assert_eq!(
+        standings.record(0, 1),
+        Record {
+            wins: 0,
+            draws: 4,
+            losses: 0,
+        }
+    );
+    let table = standings.to_string();
+    assert_eq!(table.lines().count(), 3);
+    assert!(table.lines().nth(1).unwrap().contains("0-4-0"));
+}
@@ -0,0 +1,68 @@
+use tttai::{self_play, Board, GameError, Mark, PlaceError, ReplayError, Transcript};
+
+#[test]
+fn played_game_round_trips() {
+    let board = self_play(Board::new(), |_| {});
+    let text = Transcript::from_board(&board).to_string();
+    assert_eq!(text.split(' ').count(), 9);
+    assert!(text.starts_with("X4 O0"));
+    let transcript: Transcript = text.parse().unwrap();
+    let replayed = transcript.replay().unwrap();
+    assert_eq!(replayed, board);
+    assert_eq!(replayed.state(), board.state());
+    assert_eq!(replayed.history(), board.history());
+}
+
+#[test]
+fn builds_up_and_prints_moves() {
+    let mut transcript = Transcript::new();
+    assert_eq!(transcript.to_string(), "");
+    transcript.push(Mark::O, 4);
+    transcript.push(Mark::X, 0);
+    assert_eq!(transcript.to_string(), "O4 X0");
+    let board = transcript.replay().unwrap();
+    assert_eq!(board.current_player(), Some(Mark::O));
+}
+
+#[test]
+fn rejects_bad_moves() {
+    assert_eq!("X4 Q1".parse::<Transcript>().unwrap_err().token, "Q1");
+    assert!("X".parse::<Transcript>().is_err());
+    let twice: Transcript = "X4 X0".parse().unwrap();
+    assert_eq!(twice.replay(), Err(GameError::NotYourTurn));
+    let taken: Transcript = "X4 O4".parse().unwrap();
+    assert_eq!(taken.replay(), Err(GameError::Place(PlaceError::Occupied)));
+}
+
+#[test]
+fn positions_step_through_every_move() {
+    let moves = [4, 0, 8, 2, 1, 7, 6, 3, 5];
+    let transcript: Transcript = "X4 O0 X8 O2 X1 O7 X6 O3 X5".parse().unwrap();
+    let boards = transcript.positions().unwrap();
+    assert_eq!(boards.len(), moves.len() + 1);
+    for (n, board) in boards.iter().enumerate() {
+        assert_eq!(
+            *board,
+            Board::from_moves(&moves[..n]).unwrap(),
+            "after {} moves",
+            n
+        );
+    }
+    assert_eq!(boards.last(), Some(&transcript.replay().unwrap()));
+}
+
+#[test]
+fn positions_name_the_bad_move() {
+    let transcript: Transcript = "X4 O0 X0".parse().unwrap();
+    let err = transcript.positions().unwrap_err();
+    assert_eq!(
+        err,
+        ReplayError {
+            move_number: 3,
+            mark: Mark::X,
+            index: 0,
+            error: GameError::Place(PlaceError::Occupied),
+        }
+    );
+    assert_eq!(err.to_string(), "move 3 (X0): that cell is already taken");
+}
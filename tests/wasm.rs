@@ -0,0 +1,20 @@
+#![cfg(feature = "wasm")]
+
+use tttai::wasm::WasmGame;
+
+#[test]
+fn bindings_play_a_game() {
+    let mut game = WasmGame::new();
+    assert_eq!(game.status(), "X to move");
+    assert!(game.place(4));
+    assert!(!game.place(4));
+    assert!(!game.place(9));
+    assert_eq!(game.cell(4), Some('X'));
+    assert_eq!(game.cell(0), None);
+    assert_eq!(game.cell(9), None);
+    let reply = game.ai_move().unwrap();
+    assert_eq!(game.cell(reply), Some('O'));
+    while game.ai_move().is_some() {}
+    assert_eq!(game.status(), "Tie");
+    assert!(!game.place(0));
+}